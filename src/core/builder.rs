@@ -1,11 +1,31 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use itertools::Itertools;
 use serde::ser::SerializeMap;
 use serde::{Deserialize, Serialize};
 
-use crate::core::internal::IDeviceInfoBuilder;
+use crate::core::cache::{ComponentCache, HARDWARE_TTL, SOFTWARE_TTL};
+#[cfg(feature = "async")]
+use crate::core::concurrent::probe_concurrently_async;
+use crate::core::concurrent::{probe_concurrently, ProbeLimits};
+use crate::core::error::DeviceInfoError;
+use crate::core::internal::{DuplicatePolicy, IDeviceInfoBuilder};
+use crate::core::memory::{list_memory_modules, memory_serial_numbers, total_memory};
+use crate::core::network::{list_network_adapters, physical_mac_addresses};
+use crate::core::normalize::Normalizer;
+use crate::core::placeholder::PlaceholderPolicy;
+use crate::core::profile::Profile;
+use crate::core::provenance::{Confidence, Provenance};
+use crate::core::provider::{ComponentProvider, RESERVED_NAMESPACES};
+use crate::core::sources::SourceComponents;
+use crate::core::value::ComponentValue;
+use crate::plugins::linux::os_release::read_os_release;
+use crate::plugins::linux::plugin::{LinuxBuilder, LinuxBuilderComponents};
 use crate::plugins::macos::plugin::{MacOSBuilder, MacOSBuilderComponents};
 use crate::plugins::windows::plugin::{WindowsBuilder, WindowsBuilderComponents};
 
@@ -13,15 +33,28 @@ use super::internal::BaseDeviceInfoBuilder;
 
 // use crate::plugins::{macos::plugin, windows::plugin::WindowsBuilder};
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+#[derive(Debug, Hash, PartialEq, Eq, Clone, PartialOrd, Ord)]
 pub enum MainBuilderComponents {
     UserName,
     DeviceName,
     OSPlatform,
     OSDistro,
     CpuArch,
+    MACAddresses,
+    MemoryTotal,
+    MemoryModules,
+    MemorySerialNumbers,
     WindowsBuilderComponents(WindowsBuilderComponents),
     MacOSBuilderComponents(MacOSBuilderComponents),
+    LinuxBuilderComponents(LinuxBuilderComponents),
+    SourceComponents(SourceComponents),
+    /// Component of a registered [`ComponentProvider`]
+    Provided {
+        namespace: String,
+        component: String,
+    },
+    /// Component defined by the application, see [`IMainBuilder::add_custom`]
+    Custom(String),
 }
 
 impl MainBuilderComponents {
@@ -32,12 +65,162 @@ impl MainBuilderComponents {
             MainBuilderComponents::OSPlatform => "osPlatform".to_string(),
             MainBuilderComponents::OSDistro => "osDistro".to_string(),
             MainBuilderComponents::CpuArch => "cpuArch".to_string(),
+            MainBuilderComponents::MACAddresses => "MACAddresses".to_string(),
+            MainBuilderComponents::MemoryTotal => "memoryTotal".to_string(),
+            MainBuilderComponents::MemoryModules => "memoryModules".to_string(),
+            MainBuilderComponents::MemorySerialNumbers => "memorySerialNumbers".to_string(),
             MainBuilderComponents::WindowsBuilderComponents(ref component) => {
                 format!("Windows::{}", component.as_string())
             }
             MainBuilderComponents::MacOSBuilderComponents(ref component) => {
                 format!("MacOS::{}", component.as_string())
             }
+            MainBuilderComponents::LinuxBuilderComponents(ref component) => {
+                format!("Linux::{}", component.as_string())
+            }
+            MainBuilderComponents::SourceComponents(ref component) => component.as_string(),
+            MainBuilderComponents::Provided {
+                ref namespace,
+                ref component,
+            } => format!("{}::{}", namespace, component),
+            MainBuilderComponents::Custom(ref name) => format!("Custom::{}", name),
+        }
+    }
+
+    /// Whether the value identifies a boot session rather than the device
+    pub fn is_volatile(&self) -> bool {
+        match *self {
+            MainBuilderComponents::LinuxBuilderComponents(ref component) => component.is_volatile(),
+            _ => false,
+        }
+    }
+
+    /// Where the value is read, see [`Provenance`]
+    pub fn source(&self, sysroot: &Path) -> String {
+        let windows = cfg!(target_os = "windows");
+        let macos = cfg!(target_os = "macos");
+        let custom_sysroot = sysroot != Path::new("/");
+        match *self {
+            MainBuilderComponents::UserName | MainBuilderComponents::CpuArch if custom_sysroot => {
+                format!("Not available below {}", sysroot.to_string_lossy())
+            }
+            MainBuilderComponents::DeviceName if custom_sysroot => {
+                LinuxSource("/etc/hostname").below(sysroot)
+            }
+            MainBuilderComponents::OSPlatform if custom_sysroot => {
+                format!("Linux, assumed for {}", sysroot.to_string_lossy())
+            }
+            MainBuilderComponents::OSDistro if custom_sysroot => {
+                LinuxSource("/etc/os-release (PRETTY_NAME)").below(sysroot)
+            }
+            MainBuilderComponents::UserName => "whoami::username()".to_string(),
+            MainBuilderComponents::DeviceName => "whoami::devicename()".to_string(),
+            MainBuilderComponents::OSPlatform => "whoami::platform()".to_string(),
+            MainBuilderComponents::OSDistro => "whoami::distro()".to_string(),
+            MainBuilderComponents::CpuArch => "whoami::arch()".to_string(),
+            MainBuilderComponents::MACAddresses if windows => {
+                WindowsBuilderComponents::MACAddress.source()
+            }
+            MainBuilderComponents::MACAddresses if macos => {
+                "networksetup -listallhardwareports".to_string()
+            }
+            MainBuilderComponents::MACAddresses => {
+                LinuxSource("/sys/class/net/*/address").below(sysroot)
+            }
+            MainBuilderComponents::MemoryTotal if windows => format!(
+                "WMI: {}",
+                crate::plugins::windows::plugin::TOTAL_MEMORY_QUERY
+            ),
+            MainBuilderComponents::MemoryTotal if macos => "sysctl -n hw.memsize".to_string(),
+            MainBuilderComponents::MemoryTotal => LinuxSource("/proc/meminfo").below(sysroot),
+            MainBuilderComponents::MemoryModules | MainBuilderComponents::MemorySerialNumbers
+                if windows =>
+            {
+                format!(
+                    "WMI: {}",
+                    crate::plugins::windows::plugin::MEMORY_MODULES_QUERY
+                )
+            }
+            MainBuilderComponents::MemoryModules | MainBuilderComponents::MemorySerialNumbers
+                if macos =>
+            {
+                "Not exposed on macOS".to_string()
+            }
+            MainBuilderComponents::MemoryModules | MainBuilderComponents::MemorySerialNumbers => {
+                LinuxSource("/sys/firmware/dmi/tables (SMBIOS type 17)").below(sysroot)
+            }
+            MainBuilderComponents::WindowsBuilderComponents(ref component) => component.source(),
+            MainBuilderComponents::MacOSBuilderComponents(ref component) => component.source(),
+            MainBuilderComponents::LinuxBuilderComponents(ref component) => {
+                component.source(sysroot)
+            }
+            MainBuilderComponents::SourceComponents(SourceComponents::FileContents {
+                ref path,
+                ..
+            }) => LinuxSource(path).below(sysroot),
+            MainBuilderComponents::SourceComponents(SourceComponents::EnvVar {
+                ref name, ..
+            }) => format!("Environment variable {}", name),
+            MainBuilderComponents::SourceComponents(SourceComponents::CommandOutput {
+                ref command,
+                ..
+            }) => format!("Command: {}", command.join(" ")),
+            MainBuilderComponents::Provided { ref namespace, .. } => {
+                format!("Provider '{}'", namespace)
+            }
+            MainBuilderComponents::Custom(_) => "Application".to_string(),
+        }
+    }
+
+    /// Values picked by heuristics or by the application get
+    /// [`Confidence::Medium`]
+    pub fn confidence(&self) -> Confidence {
+        match *self {
+            MainBuilderComponents::WindowsBuilderComponents(ref component) => {
+                component.confidence()
+            }
+            MainBuilderComponents::MacOSBuilderComponents(ref component) => component.confidence(),
+            MainBuilderComponents::LinuxBuilderComponents(ref component) => component.confidence(),
+            MainBuilderComponents::MACAddresses
+            | MainBuilderComponents::SourceComponents(_)
+            | MainBuilderComponents::Provided { .. }
+            | MainBuilderComponents::Custom(_) => Confidence::Medium,
+            _ => Confidence::High,
+        }
+    }
+
+    /// How long the value may be served from a [`ComponentCache`]
+    ///
+    /// Volatile and application-defined values are never cached.
+    pub fn cache_ttl(&self) -> Option<Duration> {
+        match *self {
+            _ if self.is_volatile() => None,
+            MainBuilderComponents::SourceComponents(_)
+            | MainBuilderComponents::Provided { .. }
+            | MainBuilderComponents::Custom(_) => None,
+            MainBuilderComponents::MemoryTotal | MainBuilderComponents::MemoryModules => {
+                Some(HARDWARE_TTL)
+            }
+            _ if self.normalizer().is_identifier() => Some(HARDWARE_TTL),
+            _ => Some(SOFTWARE_TTL),
+        }
+    }
+
+    /// Application-defined values are kept verbatim
+    pub fn normalizer(&self) -> Normalizer {
+        match *self {
+            MainBuilderComponents::MACAddresses => Normalizer::Mac,
+            MainBuilderComponents::MemorySerialNumbers => Normalizer::Serial,
+            MainBuilderComponents::MemoryModules
+            | MainBuilderComponents::SourceComponents(_)
+            | MainBuilderComponents::Provided { .. }
+            | MainBuilderComponents::Custom(_) => Normalizer::Verbatim,
+            MainBuilderComponents::WindowsBuilderComponents(ref component) => {
+                component.normalizer()
+            }
+            MainBuilderComponents::MacOSBuilderComponents(ref component) => component.normalizer(),
+            MainBuilderComponents::LinuxBuilderComponents(ref component) => component.normalizer(),
+            _ => Normalizer::Trim,
         }
     }
 }
@@ -63,6 +246,10 @@ impl<'de> Deserialize<'de> for MainBuilderComponents {
             "osPlatform" => Ok(MainBuilderComponents::OSPlatform),
             "osDistro" => Ok(MainBuilderComponents::OSDistro),
             "cpuArch" => Ok(MainBuilderComponents::CpuArch),
+            "MACAddresses" => Ok(MainBuilderComponents::MACAddresses),
+            "memoryTotal" => Ok(MainBuilderComponents::MemoryTotal),
+            "memoryModules" => Ok(MainBuilderComponents::MemoryModules),
+            "memorySerialNumbers" => Ok(MainBuilderComponents::MemorySerialNumbers),
             _ if s.starts_with("Windows::") => {
                 let component = s.strip_prefix("Windows::").unwrap();
                 match WindowsBuilderComponents::from_str(component) {
@@ -83,14 +270,52 @@ impl<'de> Deserialize<'de> for MainBuilderComponents {
                     ))),
                 }
             }
-            _ => Err(serde::de::Error::custom(format!(
-                "Invalid MainBuilderComponents: {}",
-                s
-            ))),
+            _ if s.starts_with("Linux::") => {
+                let component = s.strip_prefix("Linux::").unwrap();
+                match LinuxBuilderComponents::from_str(component) {
+                    Some(v) => Ok(MainBuilderComponents::LinuxBuilderComponents(v)),
+                    None => Err(serde::de::Error::custom(format!(
+                        "Invalid LinuxBuilderComponents: {}",
+                        s
+                    ))),
+                }
+            }
+            _ if s.starts_with("Custom::") => {
+                let name = s.strip_prefix("Custom::").unwrap();
+                match name.is_empty() {
+                    true => Err(serde::de::Error::custom(format!(
+                        "Invalid custom component: {}",
+                        s
+                    ))),
+                    false => Ok(MainBuilderComponents::Custom(name.to_string())),
+                }
+            }
+            _ => match SourceComponents::from_str(&s) {
+                Some(v) => Ok(MainBuilderComponents::SourceComponents(v)),
+                None => match s.split_once("::") {
+                    Some((namespace, component))
+                        if is_valid_namespace(namespace) && !component.is_empty() =>
+                    {
+                        Ok(MainBuilderComponents::Provided {
+                            namespace: namespace.to_string(),
+                            component: component.to_string(),
+                        })
+                    }
+                    _ => Err(serde::de::Error::custom(format!(
+                        "Invalid MainBuilderComponents: {}",
+                        s
+                    ))),
+                },
+            },
         }
     }
 }
 
+/// Whether `namespace` can be used by a [`ComponentProvider`]
+fn is_valid_namespace(namespace: &str) -> bool {
+    !namespace.is_empty() && !namespace.contains("::") && !RESERVED_NAMESPACES.contains(&namespace)
+}
+
 impl fmt::Display for MainBuilderComponents {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(self.as_string().as_str())
@@ -103,6 +328,44 @@ pub trait IMainBuilder: IDeviceInfoBuilder<MainBuilderComponents> {
     fn add_platform_name(&mut self) -> &mut Self;
     fn add_os_distro(&mut self) -> &mut Self;
     fn add_cpu_arch(&mut self) -> &mut Self;
+    fn add_mac_addresses(&mut self) -> &mut Self;
+    fn add_memory_total(&mut self) -> &mut Self;
+    fn add_memory_modules(&mut self) -> &mut Self;
+    fn add_memory_serial_numbers(&mut self) -> &mut Self;
+
+    /// Every component of `profile` on the platform being probed: the host,
+    /// or Linux below a custom sysroot
+    fn add_profile(&mut self, profile: Profile) -> &mut Self;
+
+    /// Contents of the file at `path`, below the sysroot
+    ///
+    /// A relative `path` is resolved against the current directory, and
+    /// fails as unsupported below a custom sysroot.
+    fn add_file_contents<P: AsRef<Path>>(&mut self, path: P, hashed: bool) -> &mut Self;
+
+    /// Value of the environment variable `name`
+    fn add_env_var(&mut self, name: &str, hashed: bool) -> &mut Self;
+
+    /// Standard output of `command` run with `args`
+    fn add_command_output(&mut self, command: &str, args: &[&str], hashed: bool) -> &mut Self;
+
+    /// `component` of the provider registered for `namespace`
+    fn add_provided(&mut self, namespace: &str, component: &str) -> &mut Self;
+
+    /// Every component of the provider registered for `namespace`
+    fn add_all_provided(&mut self, namespace: &str) -> &mut Self;
+
+    /// Add a value defined by the application (installation ID, tenant ID,
+    /// ...), serialized as `Custom::<name>`
+    fn add_custom<V>(&mut self, name: &str, value: V) -> &mut Self
+    where
+        V: Into<ComponentValue>;
+
+    /// [`Self::add_custom`] with a value computed by `get_value`
+    fn add_custom_with<F, V>(&mut self, name: &str, get_value: F) -> &mut Self
+    where
+        F: FnOnce() -> V,
+        V: Into<ComponentValue>;
 
     fn on_windows<F>(&mut self, on_windows_plugin: F) -> &mut Self
     where
@@ -111,18 +374,461 @@ pub trait IMainBuilder: IDeviceInfoBuilder<MainBuilderComponents> {
     fn on_macos<F>(&mut self, on_macos_plugin: F) -> &mut Self
     where
         F: Fn(&mut MacOSBuilder) -> &mut MacOSBuilder;
+
+    fn on_linux<F>(&mut self, on_linux_plugin: F) -> &mut Self
+    where
+        F: Fn(&mut LinuxBuilder) -> &mut LinuxBuilder;
+}
+
+/// A path of the Linux filesystem, shown below the sysroot it is read from
+struct LinuxSource<'a>(&'a str);
+
+impl LinuxSource<'_> {
+    fn below(&self, sysroot: &Path) -> String {
+        match sysroot == Path::new("/") {
+            true => self.0.to_string(),
+            false => format!("{} below {}", self.0, sysroot.to_string_lossy()),
+        }
+    }
+}
+
+/// What the probes of [`MainDeviceInfoBuilder`] need, cheap to clone so
+/// probes can run on other threads
+#[derive(Clone)]
+struct ProbeContext {
+    sysroot: PathBuf,
+    source_timeout: Duration,
+    providers: Vec<Arc<dyn ComponentProvider>>,
+    cache: Option<Arc<ComponentCache>>,
+}
+
+impl ProbeContext {
+    fn new() -> Self {
+        Self {
+            sysroot: PathBuf::from("/"),
+            source_timeout: Duration::from_secs(5),
+            providers: vec![],
+            cache: None,
+        }
+    }
+
+    fn provider(&self, namespace: &str) -> Option<&dyn ComponentProvider> {
+        self.providers
+            .iter()
+            .find(|provider| provider.namespace() == namespace)
+            .map(|provider| provider.as_ref())
+    }
+
+    fn has_custom_sysroot(&self) -> bool {
+        self.sysroot != Path::new("/")
+    }
+
+    /// [`Self::probe`], timed, its source noting when the value was served
+    /// from the cache
+    fn trace(
+        &self,
+        name: &MainBuilderComponents,
+    ) -> (Result<ComponentValue, DeviceInfoError>, Provenance) {
+        let ((result, cached), mut provenance) =
+            Provenance::trace(name.source(&self.sysroot), name.confidence(), || {
+                self.probe_cached(name)
+            });
+        if cached {
+            provenance.source = self.cached_source(provenance.source);
+        }
+        (result, provenance)
+    }
+
+    fn cached_source(&self, source: String) -> String {
+        match &self.cache {
+            Some(cache) => format!("{} (cached in {})", source, cache.path().to_string_lossy()),
+            None => source,
+        }
+    }
+
+    fn probe(&self, name: &MainBuilderComponents) -> Result<ComponentValue, DeviceInfoError> {
+        self.probe_cached(name).0
+    }
+
+    /// Serve the component from the cache when it is fresh enough, or probe
+    /// it and cache the value; also tells whether it was served from the
+    /// cache
+    ///
+    /// A tree below a sysroot is another machine, and bypasses the cache.
+    fn probe_cached(
+        &self,
+        name: &MainBuilderComponents,
+    ) -> (Result<ComponentValue, DeviceInfoError>, bool) {
+        let (cache, ttl) = match (&self.cache, name.cache_ttl()) {
+            (Some(cache), Some(ttl)) if !self.has_custom_sysroot() => (cache, ttl),
+            _ => return (self.probe_uncached(name), false),
+        };
+        let key = name.to_string();
+        if let Some(value) = cache.get(&key, ttl) {
+            return (Ok(value), true);
+        }
+
+        let result = self.probe_uncached(name);
+        if let Ok(ref value) = result {
+            // The cache is an optimization, a read-only cache directory is fine
+            let _ = cache.insert(&key, value.to_owned());
+        }
+        (result, false)
+    }
+
+    /// [`Self::probe`] without blocking the tokio runtime
+    ///
+    /// Sources are read asynchronously; they are never cached. Every other
+    /// probe (WMI, `ioreg`, sysfs, ...) runs on the blocking pool.
+    #[cfg(feature = "async")]
+    async fn probe_async(
+        self,
+        name: MainBuilderComponents,
+    ) -> (Result<ComponentValue, DeviceInfoError>, bool) {
+        match name {
+            MainBuilderComponents::SourceComponents(ref component)
+                if component.supports_sysroot() || !self.has_custom_sysroot() =>
+            {
+                let result = component
+                    .read_async(&self.sysroot, self.source_timeout)
+                    .await
+                    .map(ComponentValue::from);
+                (result, false)
+            }
+            _ => tokio::task::spawn_blocking(move || self.probe_cached(&name))
+                .await
+                .unwrap_or_else(|e| (Err(DeviceInfoError::ProbeFailed(e.to_string())), false)),
+        }
+    }
+
+    /// Probe a component, see `probe` of [`MainDeviceInfoBuilder`]
+    fn probe_uncached(
+        &self,
+        name: &MainBuilderComponents,
+    ) -> Result<ComponentValue, DeviceInfoError> {
+        match *name {
+            // A tree has no current user, and only a Linux one can be read
+            MainBuilderComponents::UserName | MainBuilderComponents::CpuArch
+                if self.has_custom_sysroot() =>
+            {
+                Err(DeviceInfoError::Unsupported(format!(
+                    "{} cannot be read below a sysroot",
+                    name
+                )))
+            }
+            MainBuilderComponents::DeviceName if self.has_custom_sysroot() => {
+                let path = self.sysroot.join("etc/hostname");
+                fs::read_to_string(&path)
+                    .map_err(|e| DeviceInfoError::from_io(&path.to_string_lossy(), e))
+                    .map(|hostname| hostname.trim().to_string())
+                    .and_then(|hostname| match hostname.is_empty() {
+                        true => Err(DeviceInfoError::NotFound(path.to_string_lossy().into())),
+                        false => Ok(hostname.into()),
+                    })
+            }
+            MainBuilderComponents::OSPlatform if self.has_custom_sysroot() => {
+                Ok(whoami::Platform::Linux.to_string().into())
+            }
+            MainBuilderComponents::OSDistro if self.has_custom_sysroot() => {
+                read_os_release(&self.sysroot)
+                    .and_then(|os_release| os_release.get("PRETTY_NAME").map(ComponentValue::from))
+                    .ok_or_else(|| {
+                        DeviceInfoError::NotFound(format!(
+                            "PRETTY_NAME in etc/os-release below {}",
+                            self.sysroot.to_string_lossy()
+                        ))
+                    })
+            }
+            MainBuilderComponents::UserName => Ok(whoami::username().into()),
+            MainBuilderComponents::DeviceName => Ok(whoami::devicename().into()),
+            MainBuilderComponents::OSPlatform => Ok(whoami::platform().to_string().into()),
+            MainBuilderComponents::OSDistro => Ok(whoami::distro().into()),
+            MainBuilderComponents::CpuArch => Ok(whoami::arch().to_string().into()),
+            MainBuilderComponents::MACAddresses => Ok(ComponentValue::list(
+                physical_mac_addresses(&list_network_adapters(&self.sysroot)),
+            )),
+            MainBuilderComponents::MemoryTotal => total_memory(&self.sysroot)
+                .map(|total| total.to_string().into())
+                .ok_or_else(|| DeviceInfoError::NotFound("Total physical memory".to_string())),
+            MainBuilderComponents::MemoryModules => list_memory_modules(&self.sysroot)
+                .map(|modules| ComponentValue::list(modules.iter().map(ToString::to_string))),
+            MainBuilderComponents::MemorySerialNumbers => list_memory_modules(&self.sysroot)
+                .map(|modules| ComponentValue::list(memory_serial_numbers(&modules))),
+            MainBuilderComponents::WindowsBuilderComponents(ref component) => {
+                match whoami::platform() == whoami::Platform::Windows && !self.has_custom_sysroot()
+                {
+                    true => WindowsBuilder::new().probe(component),
+                    false => Err(DeviceInfoError::Unsupported(format!(
+                        "{} is not available on this system",
+                        name
+                    ))),
+                }
+            }
+            MainBuilderComponents::MacOSBuilderComponents(ref component) => {
+                match whoami::platform() == whoami::Platform::MacOS && !self.has_custom_sysroot() {
+                    true => MacOSBuilder::new().probe(component),
+                    false => Err(DeviceInfoError::Unsupported(format!(
+                        "{} is not available on this system",
+                        name
+                    ))),
+                }
+            }
+            MainBuilderComponents::LinuxBuilderComponents(ref component) => {
+                match whoami::platform() == whoami::Platform::Linux || self.has_custom_sysroot() {
+                    true => LinuxBuilder::with_sysroot(&self.sysroot).probe(component),
+                    false => Err(DeviceInfoError::Unsupported(format!(
+                        "{} is not available on this system",
+                        name
+                    ))),
+                }
+            }
+            MainBuilderComponents::SourceComponents(ref component) => {
+                match component.supports_sysroot() || !self.has_custom_sysroot() {
+                    true => component
+                        .read(&self.sysroot, self.source_timeout)
+                        .map(ComponentValue::from),
+                    false => Err(DeviceInfoError::Unsupported(format!(
+                        "{} cannot be read below a sysroot",
+                        name
+                    ))),
+                }
+            }
+            MainBuilderComponents::Provided {
+                ref namespace,
+                ref component,
+            } => match self.provider(namespace) {
+                Some(provider) if provider.components().iter().any(|c| c == component) => {
+                    provider.collect(component)
+                }
+                Some(_) => Err(DeviceInfoError::Unsupported(format!(
+                    "{} is not provided by the '{}' provider",
+                    name, namespace
+                ))),
+                None => Err(DeviceInfoError::Unsupported(format!(
+                    "No provider is registered for namespace '{}'",
+                    namespace
+                ))),
+            },
+            MainBuilderComponents::Custom(_) => Err(DeviceInfoError::Unsupported(format!(
+                "{} is provided by the application and cannot be probed",
+                name
+            ))),
+        }
+    }
 }
 
 pub struct MainDeviceInfoBuilder {
     _base: BaseDeviceInfoBuilder<MainBuilderComponents>,
+    context: ProbeContext,
+    serialize_failures: bool,
+    serialize_provenance: bool,
+    probe_limits: ProbeLimits,
 }
 
 impl MainDeviceInfoBuilder {
     pub fn new() -> Self {
         Self {
             _base: BaseDeviceInfoBuilder::<MainBuilderComponents>::new(),
+            context: ProbeContext::new(),
+            serialize_failures: false,
+            serialize_provenance: false,
+            probe_limits: ProbeLimits::default(),
+        }
+    }
+
+    /// Re-root every filesystem probe (`/proc`, `/sys`, `/etc`, ...) below
+    /// `sysroot`, e.g. a tree copied from another machine
+    ///
+    /// Such a tree is always treated as a Linux system: `on_linux` runs
+    /// whatever the host platform is, while `on_windows` and `on_macos`,
+    /// whose probes cannot be re-rooted, are skipped.
+    pub fn set_sysroot<P: AsRef<Path>>(&mut self, sysroot: P) -> &mut Self {
+        self.context.sysroot = sysroot.as_ref().to_path_buf();
+        self
+    }
+
+    pub fn get_sysroot(&self) -> &Path {
+        &self.context.sysroot
+    }
+
+    /// Also serialize the probe failures, under a `failures` key
+    ///
+    /// Off by default, so machine codes only ever contain component values.
+    pub fn set_serialize_failures(&mut self, serialize_failures: bool) -> &mut Self {
+        self.serialize_failures = serialize_failures;
+        self
+    }
+
+    /// Also serialize where and when each component was collected, under a
+    /// `provenance` key
+    ///
+    /// Off by default, like [`Self::set_serialize_failures`].
+    pub fn set_serialize_provenance(&mut self, serialize_provenance: bool) -> &mut Self {
+        self.serialize_provenance = serialize_provenance;
+        self
+    }
+
+    /// How long reading a file or running a command added with
+    /// `add_file_contents` or `add_command_output` may take, 5 seconds by
+    /// default
+    pub fn set_source_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.context.source_timeout = timeout;
+        self
+    }
+
+    /// Register a provider, whose components are then added with
+    /// `add_provided` or `add_all_provided`
+    ///
+    /// The namespace must not contain `::`, be used by a built-in component
+    /// (`Linux`, `Custom`, ...) or by another provider.
+    pub fn register_provider<P>(&mut self, provider: P) -> Result<&mut Self, DeviceInfoError>
+    where
+        P: ComponentProvider + 'static,
+    {
+        let namespace = provider.namespace();
+        if !is_valid_namespace(namespace) {
+            return Err(DeviceInfoError::Unsupported(format!(
+                "Invalid provider namespace '{}'",
+                namespace
+            )));
+        }
+        if self.context.provider(namespace).is_some() {
+            return Err(DeviceInfoError::Unsupported(format!(
+                "A provider is already registered for namespace '{}'",
+                namespace
+            )));
+        }
+
+        self.context.providers.push(Arc::new(provider));
+        Ok(self)
+    }
+
+    /// Serve components from `cache`, and cache the probed values
+    ///
+    /// Each component is cached for its [`MainBuilderComponents::cache_ttl`].
+    /// Only probes run by this builder are cached: `add_*`, `try_add` and
+    /// `add_concurrently`, platform components included, but not those of
+    /// the `on_windows`, `on_macos` and `on_linux` builders.
+    pub fn set_cache(&mut self, cache: ComponentCache) -> &mut Self {
+        self.context.cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Limits of [`Self::add_concurrently`]
+    pub fn set_probe_limits(&mut self, limits: ProbeLimits) -> &mut Self {
+        self.probe_limits = limits;
+        self
+    }
+
+    /// Probe the components in parallel on a thread pool and add them
+    ///
+    /// Probes still running when the [`ProbeLimits`] are reached are
+    /// recorded as [`DeviceInfoError::TimedOut`] failures, without waiting
+    /// for them.
+    pub fn add_concurrently(&mut self, names: &[MainBuilderComponents]) -> &mut Self {
+        let context = self.context.clone();
+        let traced = Arc::new(Mutex::new(HashMap::new()));
+        let sink = Arc::clone(&traced);
+        let results = probe_concurrently(
+            names.to_vec(),
+            move |name| {
+                let (result, provenance) = context.trace(name);
+                sink.lock().unwrap().insert(name.clone(), provenance);
+                result
+            },
+            self.probe_limits,
+        );
+        self.add_traced_results(results, &traced)
+    }
+
+    /// [`Self::add_concurrently`] for async applications, see
+    /// [`probe_concurrently_async`]
+    ///
+    /// Must be called within a tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn add_async(&mut self, names: &[MainBuilderComponents]) -> &mut Self {
+        let context = self.context.clone();
+        let traced = Arc::new(Mutex::new(HashMap::new()));
+        let sink = Arc::clone(&traced);
+        let results = probe_concurrently_async(
+            names.to_vec(),
+            move |name| {
+                let (context, sink) = (context.clone(), Arc::clone(&sink));
+                let mut provenance =
+                    Provenance::new(name.source(&context.sysroot), name.confidence());
+                async move {
+                    let started = std::time::Instant::now();
+                    let (result, cached) = context.clone().probe_async(name.clone()).await;
+                    provenance.duration = started.elapsed();
+                    if cached {
+                        provenance.source = context.cached_source(provenance.source);
+                    }
+                    sink.lock().unwrap().insert(name, provenance);
+                    result
+                }
+            },
+            self.probe_limits,
+        )
+        .await;
+        self.add_traced_results(results, &traced)
+    }
+
+    /// Add concurrently probed components, with the provenance of those
+    /// which finished
+    ///
+    /// A probe which timed out has no provenance, unless it finished in the
+    /// meantime.
+    fn add_traced_results(
+        &mut self,
+        results: Vec<(
+            MainBuilderComponents,
+            Result<ComponentValue, DeviceInfoError>,
+        )>,
+        traced: &Mutex<HashMap<MainBuilderComponents, Provenance>>,
+    ) -> &mut Self {
+        let mut traced = traced.lock().unwrap();
+        for (name, result) in results {
+            self.add_probe_result(&name, result);
+            if let Some(provenance) = traced.remove(&name) {
+                self.add_provenance(&name, provenance);
+            }
+        }
+        self
+    }
+
+    fn has_custom_sysroot(&self) -> bool {
+        self.context.has_custom_sysroot()
+    }
+
+    /// The platform probed, following the rules of `on_windows`, `on_macos`
+    /// and `on_linux`
+    fn platform(&self) -> whoami::Platform {
+        match self.has_custom_sysroot() {
+            true => whoami::Platform::Linux,
+            false => whoami::platform(),
         }
     }
+
+    /// The failures of a platform builder whose value this builder stored,
+    /// so a value kept from before does not show as failed
+    fn stored_failures<K, B>(
+        &self,
+        builder: &B,
+        wrap: fn(K) -> MainBuilderComponents,
+    ) -> HashMap<MainBuilderComponents, DeviceInfoError>
+    where
+        K: Copy + std::hash::Hash + Eq + fmt::Display + Ord,
+        B: IDeviceInfoBuilder<K>,
+    {
+        builder
+            .get_failures()
+            .iter()
+            .filter(|(name, _)| {
+                self.get_components().get(&wrap(**name)) == builder.get_components().get(*name)
+            })
+            .map(|(name, e)| (wrap(*name), e.to_owned()))
+            .collect()
+    }
 }
 
 impl fmt::Display for MainDeviceInfoBuilder {
@@ -132,58 +838,208 @@ impl fmt::Display for MainDeviceInfoBuilder {
 }
 
 impl IDeviceInfoBuilder<MainBuilderComponents> for MainDeviceInfoBuilder {
-    fn get_components(&self) -> &HashMap<MainBuilderComponents, String> {
+    fn get_components(&self) -> &HashMap<MainBuilderComponents, ComponentValue> {
         &self._base.components
     }
 
-    fn get_components_mut(&mut self) -> &mut HashMap<MainBuilderComponents, String> {
+    fn get_components_mut(&mut self) -> &mut HashMap<MainBuilderComponents, ComponentValue> {
         &mut self._base.components
     }
+
+    fn get_failures(&self) -> &HashMap<MainBuilderComponents, DeviceInfoError> {
+        &self._base.failures
+    }
+
+    fn get_failures_mut(&mut self) -> &mut HashMap<MainBuilderComponents, DeviceInfoError> {
+        &mut self._base.failures
+    }
+
+    fn get_provenance(&self) -> &HashMap<MainBuilderComponents, Provenance> {
+        &self._base.provenance
+    }
+
+    fn get_provenance_mut(&mut self) -> &mut HashMap<MainBuilderComponents, Provenance> {
+        &mut self._base.provenance
+    }
+
+    fn get_duplicate_policy(&self) -> DuplicatePolicy {
+        self._base.duplicate_policy
+    }
+
+    fn set_duplicate_policy(&mut self, policy: DuplicatePolicy) -> &mut Self {
+        self._base.duplicate_policy = policy;
+        self
+    }
+
+    fn get_placeholder_policy(&self) -> PlaceholderPolicy {
+        self._base.placeholder_policy
+    }
+
+    fn set_placeholder_policy(&mut self, policy: PlaceholderPolicy) -> &mut Self {
+        self._base.placeholder_policy = policy;
+        self
+    }
+
+    fn normalize(&self, name: &MainBuilderComponents, value: ComponentValue) -> ComponentValue {
+        name.normalizer().apply(value)
+    }
+
+    fn is_identifier(&self, name: &MainBuilderComponents) -> bool {
+        name.normalizer().is_identifier()
+    }
+
+    fn source(&self, name: &MainBuilderComponents) -> String {
+        name.source(&self.context.sysroot)
+    }
+
+    fn confidence(&self, name: &MainBuilderComponents) -> Confidence {
+        name.confidence()
+    }
+
+    /// Probe a component, platform ones included
+    ///
+    /// Platform components are only probed on their own platform, under the
+    /// same rules as `on_windows`, `on_macos` and `on_linux`.
+    fn probe(&self, name: &MainBuilderComponents) -> Result<ComponentValue, DeviceInfoError> {
+        self.context.probe(name)
+    }
+
+    fn try_add(&mut self, name: &MainBuilderComponents) -> Result<&mut Self, DeviceInfoError> {
+        let (value, provenance) = self.context.trace(name);
+        self.add_component(name, value?)?;
+        Ok(self.add_provenance(name, provenance))
+    }
+
+    /// Traced by the probe context, which knows when the value came from
+    /// the cache
+    fn add_probed(&mut self, name: &MainBuilderComponents) -> &mut Self {
+        let (result, provenance) = self.context.trace(name);
+        self.add_probe_result(name, result)
+            .add_provenance(name, provenance)
+    }
 }
 
 impl IMainBuilder for MainDeviceInfoBuilder {
     fn add_user_name(&mut self) -> &mut Self {
-        self.add_component(
-            &MainBuilderComponents::UserName,
-            whoami::username().as_str(),
-        );
-        self
+        self.add_probed(&MainBuilderComponents::UserName)
     }
 
     fn add_device_name(&mut self) -> &mut Self {
-        self.add_component(
-            &MainBuilderComponents::DeviceName,
-            whoami::devicename().as_str(),
-        );
-        self
+        self.add_probed(&MainBuilderComponents::DeviceName)
     }
 
     fn add_platform_name(&mut self) -> &mut Self {
-        self.add_component(
-            &MainBuilderComponents::OSPlatform,
-            whoami::platform().to_string().as_str(),
-        );
-        self
+        self.add_probed(&MainBuilderComponents::OSPlatform)
     }
 
     fn add_os_distro(&mut self) -> &mut Self {
-        self.add_component(&MainBuilderComponents::OSDistro, whoami::distro().as_str());
-        self
+        self.add_probed(&MainBuilderComponents::OSDistro)
     }
 
     fn add_cpu_arch(&mut self) -> &mut Self {
-        self.add_component(
-            &MainBuilderComponents::CpuArch,
-            whoami::arch().to_string().as_str(),
-        );
+        self.add_probed(&MainBuilderComponents::CpuArch)
+    }
+
+    /// MAC addresses of the physical network adapters
+    fn add_mac_addresses(&mut self) -> &mut Self {
+        self.add_probed(&MainBuilderComponents::MACAddresses)
+    }
+
+    /// Total physical memory in bytes
+    fn add_memory_total(&mut self) -> &mut Self {
+        self.add_probed(&MainBuilderComponents::MemoryTotal)
+    }
+
+    /// Installed memory modules, one "slot: size MB, manufacturer, part
+    /// number, serial number" entry per module
+    fn add_memory_modules(&mut self) -> &mut Self {
+        self.add_probed(&MainBuilderComponents::MemoryModules)
+    }
+
+    /// Serial numbers of the installed memory modules
+    fn add_memory_serial_numbers(&mut self) -> &mut Self {
+        self.add_probed(&MainBuilderComponents::MemorySerialNumbers)
+    }
+
+    fn add_profile(&mut self, profile: Profile) -> &mut Self {
+        for name in profile.components(&self.platform()) {
+            self.add_probed(&name);
+        }
+        self
+    }
+
+    fn add_file_contents<P: AsRef<Path>>(&mut self, path: P, hashed: bool) -> &mut Self {
+        self.add_probed(&MainBuilderComponents::SourceComponents(
+            SourceComponents::FileContents {
+                path: path.as_ref().to_string_lossy().to_string(),
+                hashed,
+            },
+        ))
+    }
+
+    fn add_env_var(&mut self, name: &str, hashed: bool) -> &mut Self {
+        self.add_probed(&MainBuilderComponents::SourceComponents(
+            SourceComponents::EnvVar {
+                name: name.to_string(),
+                hashed,
+            },
+        ))
+    }
+
+    fn add_command_output(&mut self, command: &str, args: &[&str], hashed: bool) -> &mut Self {
+        self.add_probed(&MainBuilderComponents::SourceComponents(
+            SourceComponents::CommandOutput {
+                command: std::iter::once(command)
+                    .chain(args.iter().copied())
+                    .map(|arg| arg.to_string())
+                    .collect(),
+                hashed,
+            },
+        ))
+    }
+
+    fn add_provided(&mut self, namespace: &str, component: &str) -> &mut Self {
+        self.add_probed(&MainBuilderComponents::Provided {
+            namespace: namespace.to_string(),
+            component: component.to_string(),
+        })
+    }
+
+    fn add_all_provided(&mut self, namespace: &str) -> &mut Self {
+        let components = self
+            .context
+            .provider(namespace)
+            .map(|provider| provider.components())
+            .unwrap_or_default();
+        for component in components {
+            self.add_provided(namespace, &component);
+        }
         self
     }
 
+    fn add_custom<V>(&mut self, name: &str, value: V) -> &mut Self
+    where
+        V: Into<ComponentValue>,
+    {
+        let name = MainBuilderComponents::Custom(name.to_string());
+        let provenance = Provenance::new(name.source(&self.context.sysroot), name.confidence());
+        self.merge_component(&name, value)
+            .add_provenance(&name, provenance)
+    }
+
+    fn add_custom_with<F, V>(&mut self, name: &str, get_value: F) -> &mut Self
+    where
+        F: FnOnce() -> V,
+        V: Into<ComponentValue>,
+    {
+        self.add_custom(name, get_value())
+    }
+
     fn on_windows<F>(&mut self, on_windows_plugin: F) -> &mut Self
     where
         F: Fn(&mut WindowsBuilder) -> &mut WindowsBuilder,
     {
-        match whoami::platform() == whoami::Platform::Windows {
+        match whoami::platform() == whoami::Platform::Windows && !self.has_custom_sysroot() {
             true => {
                 let mut windows_builder = WindowsBuilder::new();
                 on_windows_plugin(&mut windows_builder);
@@ -199,6 +1055,22 @@ impl IMainBuilder for MainDeviceInfoBuilder {
                         })
                         .collect()),
                 );
+                self.extend_failures(&self.stored_failures(
+                    &windows_builder,
+                    MainBuilderComponents::WindowsBuilderComponents,
+                ));
+                self.extend_provenance(
+                    &(windows_builder
+                        .get_provenance()
+                        .iter()
+                        .map(|provenance| {
+                            (
+                                MainBuilderComponents::WindowsBuilderComponents(*provenance.0),
+                                provenance.1.to_owned(),
+                            )
+                        })
+                        .collect()),
+                );
                 self
             }
             false => self,
@@ -209,7 +1081,7 @@ impl IMainBuilder for MainDeviceInfoBuilder {
     where
         F: Fn(&mut MacOSBuilder) -> &mut MacOSBuilder,
     {
-        match whoami::platform() == whoami::Platform::MacOS {
+        match whoami::platform() == whoami::Platform::MacOS && !self.has_custom_sysroot() {
             true => {
                 let mut macos_builder = MacOSBuilder::new();
                 on_macos_plugin(&mut macos_builder);
@@ -225,8 +1097,66 @@ impl IMainBuilder for MainDeviceInfoBuilder {
                         })
                         .collect()),
                 );
-                self
-            }
+                self.extend_failures(&self.stored_failures(
+                    &macos_builder,
+                    MainBuilderComponents::MacOSBuilderComponents,
+                ));
+                self.extend_provenance(
+                    &(macos_builder
+                        .get_provenance()
+                        .iter()
+                        .map(|provenance| {
+                            (
+                                MainBuilderComponents::MacOSBuilderComponents(*provenance.0),
+                                provenance.1.to_owned(),
+                            )
+                        })
+                        .collect()),
+                );
+                self
+            }
+            false => self,
+        }
+    }
+
+    fn on_linux<F>(&mut self, on_linux_plugin: F) -> &mut Self
+    where
+        F: Fn(&mut LinuxBuilder) -> &mut LinuxBuilder,
+    {
+        match whoami::platform() == whoami::Platform::Linux || self.has_custom_sysroot() {
+            true => {
+                let mut linux_builder = LinuxBuilder::with_sysroot(&self.context.sysroot);
+                on_linux_plugin(&mut linux_builder);
+                self.extend_components(
+                    &(linux_builder
+                        .get_components()
+                        .iter()
+                        .map(|component| {
+                            (
+                                MainBuilderComponents::LinuxBuilderComponents(*component.0),
+                                component.1.to_owned(),
+                            )
+                        })
+                        .collect()),
+                );
+                self.extend_failures(&self.stored_failures(
+                    &linux_builder,
+                    MainBuilderComponents::LinuxBuilderComponents,
+                ));
+                self.extend_provenance(
+                    &(linux_builder
+                        .get_provenance()
+                        .iter()
+                        .map(|provenance| {
+                            (
+                                MainBuilderComponents::LinuxBuilderComponents(*provenance.0),
+                                provenance.1.to_owned(),
+                            )
+                        })
+                        .collect()),
+                );
+                self
+            }
             false => self,
         }
     }
@@ -240,10 +1170,18 @@ impl Serialize for MainDeviceInfoBuilder {
     where
         S: serde::Serializer,
     {
-        let mut map = serializer.serialize_map(Some(self._base.components.len()))?;
+        let mut map = serializer.serialize_map(None)?;
         for (k, v) in self._base.components.iter().sorted_by_key(|el| el.0) {
             map.serialize_entry(k, v)?;
         }
+        if self.serialize_failures && !self._base.failures.is_empty() {
+            let failures: BTreeMap<_, _> = self._base.failures.iter().collect();
+            map.serialize_entry("failures", &failures)?;
+        }
+        if self.serialize_provenance && !self._base.provenance.is_empty() {
+            let provenance: BTreeMap<_, _> = self._base.provenance.iter().collect();
+            map.serialize_entry("provenance", &provenance)?;
+        }
         map.end()
     }
 }
@@ -253,9 +1191,40 @@ impl<'de> Deserialize<'de> for MainDeviceInfoBuilder {
     where
         D: serde::Deserializer<'de>,
     {
-        let components = HashMap::<MainBuilderComponents, String>::deserialize(deserializer)?;
+        #[derive(Deserialize)]
+        struct SerializedBuilder {
+            #[serde(default)]
+            failures: HashMap<MainBuilderComponents, DeviceInfoError>,
+            #[serde(default)]
+            provenance: HashMap<MainBuilderComponents, Provenance>,
+            #[serde(flatten)]
+            components: HashMap<MainBuilderComponents, ComponentValue>,
+        }
+
+        let serialized = SerializedBuilder::deserialize(deserializer)?;
+        let serialize_failures = !serialized.failures.is_empty();
+        let serialize_provenance = !serialized.provenance.is_empty();
+        // Machine codes issued before normalization hold raw values
+        let components = serialized
+            .components
+            .into_iter()
+            .map(|(name, value)| {
+                let value = name.normalizer().apply(value);
+                (name, value)
+            })
+            .collect();
         Ok(Self {
-            _base: BaseDeviceInfoBuilder::<MainBuilderComponents> { components },
+            _base: BaseDeviceInfoBuilder::<MainBuilderComponents> {
+                components,
+                failures: serialized.failures,
+                provenance: serialized.provenance,
+                duplicate_policy: DuplicatePolicy::default(),
+                placeholder_policy: PlaceholderPolicy::default(),
+            },
+            context: ProbeContext::new(),
+            serialize_failures,
+            serialize_provenance,
+            probe_limits: ProbeLimits::default(),
         })
     }
 }
@@ -269,6 +1238,7 @@ impl Default for MainDeviceInfoBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::plugins::linux::plugin::ILinuxBuilder;
     use crate::plugins::macos::plugin::IMacOSBuilder;
 
     #[test]
@@ -327,4 +1297,730 @@ mod tests {
         };
         println!("{:?}", deserialized.get_components());
     }
+
+    #[test]
+    fn test_main_builder_linux_round_trip() {
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder.add_user_name().on_linux(|linux_builder| {
+            linux_builder
+                .add_machine_id()
+                .add_product_uuid()
+                .add_board_serial_number()
+        });
+        let serialized = serde_json::to_string(&builder).unwrap();
+
+        let deserialized: MainDeviceInfoBuilder = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.get_components(), builder.get_components());
+
+        let deserialized: MainDeviceInfoBuilder =
+            serde_json::from_str(r#"{"Linux::machineId":"abc","Linux::chassisSerialNumber":""}"#)
+                .unwrap();
+        assert_eq!(
+            deserialized
+                .get_components()
+                .get(&MainBuilderComponents::LinuxBuilderComponents(
+                    LinuxBuilderComponents::MachineId
+                )),
+            Some(&"abc".into())
+        );
+        assert!(serde_json::from_str::<MainDeviceInfoBuilder>(r#"{"Linux::unknown":""}"#).is_err());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_main_builder_sysroot() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        root.file("etc/machine-id", "0123456789abcdef0123456789abcdef\n");
+
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .set_sysroot(root.path())
+            .add_mac_addresses()
+            .on_linux(|linux_builder| linux_builder.add_machine_id());
+
+        assert_eq!(
+            builder
+                .get_components()
+                .get(&MainBuilderComponents::LinuxBuilderComponents(
+                    LinuxBuilderComponents::MachineId
+                )),
+            Some(&"0123456789abcdef0123456789abcdef".into())
+        );
+        assert_eq!(
+            builder
+                .get_components()
+                .get(&MainBuilderComponents::MACAddresses),
+            Some(&ComponentValue::list(Vec::<String>::new()))
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_main_builder_sysroot_is_not_the_host() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder.set_sysroot(root.path());
+
+        for name in [
+            MainBuilderComponents::UserName,
+            MainBuilderComponents::CpuArch,
+        ] {
+            assert!(matches!(
+                builder.probe(&name),
+                Err(DeviceInfoError::Unsupported(_))
+            ));
+        }
+        for name in [
+            MainBuilderComponents::DeviceName,
+            MainBuilderComponents::OSDistro,
+        ] {
+            assert!(matches!(
+                builder.probe(&name),
+                Err(DeviceInfoError::NotFound(_))
+            ));
+            assert!(builder
+                .source(&name)
+                .ends_with(&*root.path().to_string_lossy()));
+        }
+        assert_eq!(
+            builder.probe(&MainBuilderComponents::OSPlatform),
+            Ok("Linux".into())
+        );
+
+        root.file("etc/hostname", "customer-host\n").file(
+            "etc/os-release",
+            "NAME=\"Rocky Linux\"\nPRETTY_NAME=\"Rocky Linux 9.3 (Blue Onyx)\"\n",
+        );
+        assert_eq!(
+            builder.probe(&MainBuilderComponents::DeviceName),
+            Ok("customer-host".into())
+        );
+        assert_eq!(
+            builder.probe(&MainBuilderComponents::OSDistro),
+            Ok("Rocky Linux 9.3 (Blue Onyx)".into())
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_main_builder_try_add() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        root.file("etc/machine-id", "0123456789abcdef0123456789abcdef\n");
+
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder.set_sysroot(root.path());
+
+        let machine_id =
+            MainBuilderComponents::LinuxBuilderComponents(LinuxBuilderComponents::MachineId);
+        let boot_id = MainBuilderComponents::LinuxBuilderComponents(LinuxBuilderComponents::BootId);
+        let guid = MainBuilderComponents::WindowsBuilderComponents(WindowsBuilderComponents::Guid);
+
+        assert!(builder.try_add(&machine_id).is_ok());
+        assert!(matches!(
+            builder.try_add(&boot_id),
+            Err(DeviceInfoError::NotFound(_))
+        ));
+        assert!(matches!(
+            builder.try_add(&guid),
+            Err(DeviceInfoError::Unsupported(_))
+        ));
+        assert_eq!(builder.get_components().len(), 1);
+
+        // The infallible variants record an empty value instead of panicking
+        builder
+            .on_linux(|linux_builder| linux_builder.add_boot_id())
+            .add_probed(&guid);
+        assert_eq!(builder.get_components()[&boot_id], "");
+        assert_eq!(builder.get_components()[&guid], "");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_main_builder_on_linux_twice() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        root.file("etc/machine-id", "0123456789abcdef0123456789abcdef\n");
+        let machine_id =
+            MainBuilderComponents::LinuxBuilderComponents(LinuxBuilderComponents::MachineId);
+
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .set_sysroot(root.path())
+            .on_linux(|linux_builder| linux_builder.add_machine_id())
+            .on_linux(|linux_builder| linux_builder.add_machine_id());
+        assert!(matches!(
+            builder.get_failures()[&machine_id],
+            DeviceInfoError::DuplicateComponent(_)
+        ));
+
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .set_sysroot(root.path())
+            .set_duplicate_policy(DuplicatePolicy::KeepFirst)
+            .on_linux(|linux_builder| linux_builder.add_machine_id())
+            .on_linux(|linux_builder| linux_builder.add_machine_id().add_boot_id());
+        assert!(!builder.get_failures().contains_key(&machine_id));
+        assert_eq!(
+            builder.get_components()[&machine_id],
+            "0123456789abcdef0123456789abcdef"
+        );
+
+        // A failure of the platform builder only comes along with its value
+        let board_serial_number = MainBuilderComponents::LinuxBuilderComponents(
+            LinuxBuilderComponents::BoardSerialNumber,
+        );
+        builder
+            .merge_component(&board_serial_number, "MB-1234")
+            .on_linux(|linux_builder| linux_builder.add_board_serial_number());
+        assert_eq!(builder.get_components()[&board_serial_number], "MB-1234");
+        assert!(!builder.get_failures().contains_key(&board_serial_number));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_main_builder_failures() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        root.file("etc/machine-id", "0123456789abcdef0123456789abcdef\n");
+
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .set_sysroot(root.path())
+            .on_linux(|linux_builder| linux_builder.add_machine_id().add_board_serial_number());
+
+        let board_serial_number = MainBuilderComponents::LinuxBuilderComponents(
+            LinuxBuilderComponents::BoardSerialNumber,
+        );
+        assert_eq!(builder.get_failures().len(), 1);
+        assert!(matches!(
+            builder.get_failures()[&board_serial_number],
+            DeviceInfoError::NotFound(_)
+        ));
+        assert!(builder
+            .to_string()
+            .contains("Linux::boardSerialNumber: <unavailable: Not found: "));
+        assert!(builder
+            .to_string()
+            .contains("Linux::machineId: 0123456789abcdef0123456789abcdef"));
+
+        // Failures are left out of the serialized form unless asked for
+        let serialized = serde_json::to_string(&builder).unwrap();
+        assert!(!serialized.contains("failures"));
+
+        let serialized = serde_json::to_string(builder.set_serialize_failures(true)).unwrap();
+        assert!(serialized.contains(r#""failures":{"Linux::boardSerialNumber":{"NotFound":"#));
+        let deserialized: MainDeviceInfoBuilder = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.get_components(), builder.get_components());
+        assert_eq!(deserialized.get_failures(), builder.get_failures());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_main_builder_memory() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        root.file("proc/meminfo", "MemTotal:       16301036 kB\n")
+            .file(
+                "sys/firmware/dmi/tables/smbios_entry_point",
+                include_bytes!("fixtures/smbios3_entry_point.bin"),
+            )
+            .file(
+                "sys/firmware/dmi/tables/DMI",
+                include_bytes!("fixtures/smbios3_dmi.bin"),
+            );
+
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .set_sysroot(root.path())
+            .add_memory_total()
+            .add_memory_modules()
+            .add_memory_serial_numbers();
+
+        let components = builder.get_components();
+        assert_eq!(
+            components[&MainBuilderComponents::MemoryTotal],
+            "16692260864"
+        );
+        assert_eq!(
+            components[&MainBuilderComponents::MemoryModules],
+            ComponentValue::list([
+                "DIMM1: 8192 MB, Samsung, M378A2K43CB1-CTD, 1A2B3C4D",
+                "DIMM2: 65536 MB, SK Hynix, HMA82GU6CJR8N-VK, 5E6F7A8B",
+            ])
+        );
+        assert_eq!(
+            components[&MainBuilderComponents::MemorySerialNumbers],
+            ComponentValue::list(["1A2B3C4D", "5E6F7A8B"])
+        );
+        assert_eq!(
+            serde_json::to_value(&builder).unwrap()["memorySerialNumbers"],
+            serde_json::json!(["1A2B3C4D", "5E6F7A8B"])
+        );
+    }
+
+    #[test]
+    fn test_main_builder_memory_modules_without_smbios() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder.set_sysroot(root.path());
+
+        for name in [
+            MainBuilderComponents::MemoryModules,
+            MainBuilderComponents::MemorySerialNumbers,
+        ] {
+            assert!(matches!(
+                builder.probe(&name),
+                Err(DeviceInfoError::NotFound(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_main_builder_custom() {
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .add_custom("tenantId", "acme")
+            .add_custom_with("licenseSeats", || {
+                vec!["seat-2".to_string(), "seat-1".to_string()]
+            });
+
+        let serialized = serde_json::to_string(&builder).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"Custom::licenseSeats":["seat-1","seat-2"],"Custom::tenantId":"acme"}"#
+        );
+
+        let deserialized: MainDeviceInfoBuilder = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.get_components(), builder.get_components());
+        assert!(matches!(
+            builder.probe(&MainBuilderComponents::Custom("tenantId".to_string())),
+            Err(DeviceInfoError::Unsupported(_))
+        ));
+        assert!(serde_json::from_str::<MainDeviceInfoBuilder>(r#"{"Custom::":"x"}"#).is_err());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_main_builder_sources() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        root.file("etc/app.conf", "tenant=acme\n");
+
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .set_sysroot(root.path())
+            .add_file_contents("/etc/app.conf", false)
+            .add_env_var("DEVICE_INFO_TEST_UNSET", false)
+            .add_command_output("echo", &["vendor"], true);
+
+        let serialized = serde_json::to_value(&builder).unwrap();
+        assert_eq!(serialized["File::/etc/app.conf"], "tenant=acme");
+        assert_eq!(serialized["Env::DEVICE_INFO_TEST_UNSET"], "");
+        assert_eq!(serialized[r#"CommandSha256::["echo","vendor"]"#], "");
+        assert!(matches!(
+            builder.get_failures()[&MainBuilderComponents::SourceComponents(
+                SourceComponents::CommandOutput {
+                    command: vec!["echo".to_string(), "vendor".to_string()],
+                    hashed: true,
+                }
+            )],
+            DeviceInfoError::Unsupported(_)
+        ));
+
+        let deserialized: MainDeviceInfoBuilder = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized.get_components(), builder.get_components());
+    }
+
+    struct AcmeProvider;
+
+    impl ComponentProvider for AcmeProvider {
+        fn namespace(&self) -> &str {
+            "Acme"
+        }
+
+        fn components(&self) -> Vec<String> {
+            vec!["licenseSeat".to_string(), "dongleIds".to_string()]
+        }
+
+        fn collect(&self, component: &str) -> Result<ComponentValue, DeviceInfoError> {
+            match component {
+                "licenseSeat" => Ok("seat-42".into()),
+                _ => Err(DeviceInfoError::NotFound("No dongle attached".to_string())),
+            }
+        }
+    }
+
+    #[test]
+    fn test_main_builder_provider() {
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder.register_provider(AcmeProvider).unwrap();
+        builder
+            .add_all_provided("Acme")
+            .add_provided("Acme", "unknown");
+
+        let serialized = serde_json::to_string(&builder).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"Acme::dongleIds":"","Acme::licenseSeat":"seat-42","Acme::unknown":""}"#
+        );
+        assert!(matches!(
+            builder.get_failures()[&MainBuilderComponents::Provided {
+                namespace: "Acme".to_string(),
+                component: "unknown".to_string(),
+            }],
+            DeviceInfoError::Unsupported(_)
+        ));
+
+        let deserialized: MainDeviceInfoBuilder = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.get_components(), builder.get_components());
+
+        assert!(builder.register_provider(AcmeProvider).is_err());
+    }
+
+    #[test]
+    fn test_main_builder_provider_reserved_namespace() {
+        struct LinuxProvider;
+
+        impl ComponentProvider for LinuxProvider {
+            fn namespace(&self) -> &str {
+                "Linux"
+            }
+
+            fn components(&self) -> Vec<String> {
+                vec![]
+            }
+
+            fn collect(&self, _: &str) -> Result<ComponentValue, DeviceInfoError> {
+                Ok(ComponentValue::default())
+            }
+        }
+
+        assert!(MainDeviceInfoBuilder::new()
+            .register_provider(LinuxProvider)
+            .is_err());
+    }
+
+    #[test]
+    fn test_main_builder_normalizes_values() {
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .add_component(
+                &MainBuilderComponents::MACAddresses,
+                ComponentValue::list(["AA-BB-CC-00-11-22"]),
+            )
+            .unwrap()
+            .add_custom("tenantId", " Acme ");
+
+        assert_eq!(
+            builder.get_components()[&MainBuilderComponents::MACAddresses],
+            ComponentValue::list(["aa:bb:cc:00:11:22"])
+        );
+        assert_eq!(
+            builder.get_components()[&MainBuilderComponents::Custom("tenantId".to_string())],
+            " Acme "
+        );
+
+        // Raw values of older machine codes still match
+        let deserialized: MainDeviceInfoBuilder = serde_json::from_str(
+            r#"{"MACAddresses":["AA:BB:CC:00:11:22"],"Linux::productUuid":"4C4C4544-0042-3510-8052-B4C04F335931 "}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            deserialized.get_components()[&MainBuilderComponents::MACAddresses],
+            builder.get_components()[&MainBuilderComponents::MACAddresses]
+        );
+        assert_eq!(
+            deserialized.get_components()[&MainBuilderComponents::LinuxBuilderComponents(
+                LinuxBuilderComponents::ProductUuid
+            )],
+            "44454c4c-4200-1035-8052-b4c04f335931"
+        );
+    }
+
+    #[test]
+    fn test_main_builder_placeholders() {
+        let component = MainBuilderComponents::LinuxBuilderComponents(
+            LinuxBuilderComponents::BoardSerialNumber,
+        );
+
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder.merge_component(&component, "Default string");
+        assert_eq!(builder.get_components()[&component], "DEFAULT STRING");
+        assert_eq!(
+            builder.get_failures()[&component],
+            DeviceInfoError::Placeholder("DEFAULT STRING".to_string())
+        );
+
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .set_placeholder_policy(PlaceholderPolicy::Drop)
+            .merge_component(&component, "Default string")
+            .add_custom("seat", "None");
+        assert_eq!(builder.get_components()[&component], "");
+        assert!(builder.is_unreliable(&component));
+        assert!(!builder.is_unreliable(&MainBuilderComponents::Custom("seat".to_string())));
+
+        // Only the value which ends up stored is flagged
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .set_duplicate_policy(DuplicatePolicy::KeepFirst)
+            .merge_component(&component, "MB-1234")
+            .merge_component(&component, "Default string");
+        assert_eq!(builder.get_components()[&component], "MB-1234");
+        assert!(!builder.is_unreliable(&component));
+
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .set_duplicate_policy(DuplicatePolicy::Overwrite)
+            .merge_component(&component, "Default string")
+            .merge_component(&component, "MB-1234");
+        assert_eq!(builder.get_components()[&component], "MB-1234");
+        assert!(!builder.is_unreliable(&component));
+    }
+
+    #[test]
+    fn test_main_builder_add_concurrently() {
+        struct SlowProvider;
+
+        impl ComponentProvider for SlowProvider {
+            fn namespace(&self) -> &str {
+                "Slow"
+            }
+
+            fn components(&self) -> Vec<String> {
+                vec!["fast".to_string(), "hung".to_string()]
+            }
+
+            fn collect(&self, component: &str) -> Result<ComponentValue, DeviceInfoError> {
+                if component == "hung" {
+                    std::thread::sleep(Duration::from_secs(5));
+                }
+                Ok(component.into())
+            }
+        }
+
+        let provided = |component: &str| MainBuilderComponents::Provided {
+            namespace: "Slow".to_string(),
+            component: component.to_string(),
+        };
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .register_provider(SlowProvider)
+            .unwrap()
+            .set_probe_limits(ProbeLimits {
+                probe_timeout: Duration::from_millis(100),
+                total_timeout: Duration::from_secs(1),
+                threads: 2,
+            });
+
+        let started = std::time::Instant::now();
+        builder.add_concurrently(&[
+            provided("hung"),
+            provided("fast"),
+            MainBuilderComponents::CpuArch,
+        ]);
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(builder.get_components()[&provided("fast")], "fast");
+        assert_eq!(builder.get_components()[&provided("hung")], "");
+        assert!(matches!(
+            builder.get_failures()[&provided("hung")],
+            DeviceInfoError::TimedOut(_)
+        ));
+        assert!(builder
+            .get_components()
+            .contains_key(&MainBuilderComponents::CpuArch));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_main_builder_cache() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        let path = root.path().join("components.json");
+        ComponentCache::open_with_binding(&path, b"secret", Some(b"machine"))
+            .insert("cpuArch", "cached-arch".into())
+            .unwrap();
+
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .set_cache(ComponentCache::open_with_binding(
+                &path,
+                b"secret",
+                Some(b"machine"),
+            ))
+            .add_cpu_arch()
+            .add_platform_name();
+        assert_eq!(
+            builder.get_components()[&MainBuilderComponents::CpuArch],
+            "cached-arch"
+        );
+
+        let cache = ComponentCache::open_with_binding(&path, b"secret", Some(b"machine"));
+        assert_eq!(
+            cache.get("osPlatform", SOFTWARE_TTL),
+            Some(builder.get_components()[&MainBuilderComponents::OSPlatform].to_owned())
+        );
+
+        let linux = MainBuilderComponents::LinuxBuilderComponents;
+        assert_eq!(linux(LinuxBuilderComponents::BootId).cache_ttl(), None);
+        assert_eq!(
+            linux(LinuxBuilderComponents::ProductUuid).cache_ttl(),
+            Some(HARDWARE_TTL)
+        );
+        assert_eq!(
+            MainBuilderComponents::Custom("seat".to_string()).cache_ttl(),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(all(unix, feature = "async"))]
+    fn test_main_builder_add_async() {
+        let command = MainBuilderComponents::SourceComponents(SourceComponents::CommandOutput {
+            command: vec!["echo".to_string(), "edge-01".to_string()],
+            hashed: false,
+        });
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let mut builder = MainDeviceInfoBuilder::new();
+        runtime.block_on(builder.add_async(&[
+            command.clone(),
+            MainBuilderComponents::CpuArch,
+            MainBuilderComponents::Custom("seat".to_string()),
+        ]));
+
+        assert_eq!(builder.get_components()[&command], "edge-01");
+        assert_eq!(
+            builder.get_components()[&MainBuilderComponents::CpuArch],
+            whoami::arch().to_string().as_str()
+        );
+        assert!(matches!(
+            builder.get_failures()[&MainBuilderComponents::Custom("seat".to_string())],
+            DeviceInfoError::Unsupported(_)
+        ));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_main_builder_provenance() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        root.file("etc/machine-id", "0123456789abcdef0123456789abcdef\n");
+        root.file("sys/class/dmi/id/board_serial", "Default string\n");
+        let linux = MainBuilderComponents::LinuxBuilderComponents;
+
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .set_sysroot(root.path())
+            .add_custom("seat", "7")
+            .add_concurrently(&[linux(LinuxBuilderComponents::ProductUuid)])
+            .on_linux(|linux_builder| linux_builder.add_machine_id().add_board_serial_number());
+
+        let provenance = builder.get_provenance();
+        let machine_id = &provenance[&linux(LinuxBuilderComponents::MachineId)];
+        assert_eq!(
+            machine_id.source,
+            format!(
+                "/etc/machine-id, /var/lib/dbus/machine-id below {}",
+                root.path().to_string_lossy()
+            )
+        );
+        assert_eq!(machine_id.confidence, Confidence::High);
+        // A placeholder, and a missing file
+        assert_eq!(
+            provenance[&linux(LinuxBuilderComponents::BoardSerialNumber)].confidence,
+            Confidence::Low
+        );
+        assert_eq!(
+            provenance[&linux(LinuxBuilderComponents::ProductUuid)].confidence,
+            Confidence::Low
+        );
+        assert_eq!(
+            provenance[&MainBuilderComponents::Custom("seat".to_string())].source,
+            "Application"
+        );
+
+        let json = serde_json::to_value(builder.set_serialize_provenance(true)).unwrap();
+        assert_eq!(json["provenance"]["Linux::machineId"]["confidence"], "high");
+        let restored: MainDeviceInfoBuilder = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            restored.get_provenance()[&linux(LinuxBuilderComponents::MachineId)].source,
+            builder.get_provenance()[&linux(LinuxBuilderComponents::MachineId)].source
+        );
+        assert!(
+            serde_json::to_value(MainDeviceInfoBuilder::new()).unwrap()["provenance"].is_null()
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_main_builder_provenance_cached() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        let path = root.path().join("components.json");
+        ComponentCache::open_with_binding(&path, b"secret", Some(b"machine"))
+            .insert("cpuArch", "cached-arch".into())
+            .unwrap();
+
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .set_cache(ComponentCache::open_with_binding(
+                &path,
+                b"secret",
+                Some(b"machine"),
+            ))
+            .add_cpu_arch()
+            .add_platform_name();
+        assert_eq!(
+            builder.get_provenance()[&MainBuilderComponents::CpuArch].source,
+            format!("whoami::arch() (cached in {})", path.to_string_lossy())
+        );
+
+        // Probed, then cached for the next run
+        assert_eq!(
+            builder.get_provenance()[&MainBuilderComponents::OSPlatform].source,
+            "whoami::platform()"
+        );
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .set_cache(ComponentCache::open_with_binding(
+                &path,
+                b"secret",
+                Some(b"machine"),
+            ))
+            .add_concurrently(&[MainBuilderComponents::OSPlatform]);
+        assert_eq!(
+            builder.get_provenance()[&MainBuilderComponents::OSPlatform].source,
+            format!("whoami::platform() (cached in {})", path.to_string_lossy())
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_main_builder_add_profile() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        root.file("etc/machine-id", "0123456789abcdef0123456789abcdef\n");
+
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .set_sysroot(root.path())
+            .add_profile(Profile::Licensing);
+
+        let mut names: Vec<String> = builder
+            .get_components()
+            .keys()
+            .map(|name| name.to_string())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "Linux::boardSerialNumber",
+                "Linux::machineId",
+                "Linux::productUuid",
+                "userName"
+            ]
+        );
+        assert_eq!(
+            builder.get_components()
+                [&MainBuilderComponents::LinuxBuilderComponents(LinuxBuilderComponents::MachineId)],
+            "0123456789abcdef0123456789abcdef"
+        );
+    }
 }
@@ -0,0 +1,293 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::core::error::DeviceInfoError;
+use crate::core::internal::IDeviceInfoBuilder;
+use crate::core::value::ComponentValue;
+use crate::plugins::linux::plugin::{LinuxBuilder, LinuxBuilderComponents};
+use crate::plugins::macos::plugin::{MacOSBuilder, MacOSBuilderComponents};
+use crate::plugins::windows::plugin::{WindowsBuilder, WindowsBuilderComponents};
+
+/// How long stable hardware identifiers are cached
+pub const HARDWARE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// How long other cacheable values (OS version, user name, ...) are cached
+pub const SOFTWARE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheEntry {
+    value: ComponentValue,
+    /// Seconds since the Unix epoch
+    collected_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    entries: BTreeMap<String, CacheEntry>,
+    /// Hex HMAC-SHA256 of the machine binding and `entries`
+    mac: String,
+}
+
+/// Snapshot of collected components persisted between runs
+///
+/// The file is authenticated with HMAC-SHA256 over the entries and a value
+/// read live from the machine, see [`machine_binding`]: a cache edited by
+/// hand, written with another key or copied from another machine is ignored
+/// and replaced.
+pub struct ComponentCache {
+    path: PathBuf,
+    key: Vec<u8>,
+    /// Without one, nothing is read from or written to the cache
+    binding: Option<Vec<u8>>,
+    entries: Mutex<BTreeMap<String, CacheEntry>>,
+}
+
+impl ComponentCache {
+    /// Open the cache at `path` for this machine, starting empty when it is
+    /// missing or invalid
+    pub fn open<P: AsRef<Path>>(path: P, key: &[u8]) -> Self {
+        Self::open_with_binding(path, key, machine_binding().as_deref())
+    }
+
+    /// [`Self::open`], bound to `binding` instead of [`machine_binding`]
+    pub fn open_with_binding<P: AsRef<Path>>(path: P, key: &[u8], binding: Option<&[u8]>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let entries = fs::read(&path)
+            .ok()
+            .and_then(|content| serde_json::from_slice::<CacheFile>(&content).ok())
+            .filter(|file| sign(key, binding, &file.entries).is_some_and(|mac| mac == file.mac))
+            .map(|file| file.entries)
+            .unwrap_or_default();
+
+        Self {
+            path,
+            key: key.to_vec(),
+            binding: binding.map(|binding| binding.to_vec()),
+            entries: Mutex::new(entries),
+        }
+    }
+
+    /// Open `<app_name>/components.json` in the platform cache directory
+    pub fn open_default(app_name: &str, key: &[u8]) -> Option<Self> {
+        platform_cache_dir().map(|dir| Self::open(dir.join(app_name).join("components.json"), key))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The cached value of `name`, unless it is older than `ttl`
+    pub fn get(&self, name: &str, ttl: Duration) -> Option<ComponentValue> {
+        let now = now();
+        self.entries
+            .lock()
+            .unwrap()
+            .get(name)
+            .filter(|entry| entry.collected_at <= now && now - entry.collected_at < ttl.as_secs())
+            .map(|entry| entry.value.to_owned())
+    }
+
+    /// Cache `value` and write the cache to disk
+    pub fn insert(&self, name: &str, value: ComponentValue) -> Result<(), DeviceInfoError> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(
+            name.to_string(),
+            CacheEntry {
+                value,
+                collected_at: now(),
+            },
+        );
+        self.write(&entries)
+    }
+
+    /// Forget every value, on disk as well
+    pub fn clear(&self) -> Result<(), DeviceInfoError> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.clear();
+        self.write(&entries)
+    }
+
+    fn write(&self, entries: &BTreeMap<String, CacheEntry>) -> Result<(), DeviceInfoError> {
+        let source = self.path.to_string_lossy();
+        let file = CacheFile {
+            entries: entries.to_owned(),
+            mac: sign(&self.key, self.binding.as_deref(), entries)
+                .ok_or_else(|| DeviceInfoError::Parse(format!("{}: cannot sign", source)))?,
+        };
+        let content =
+            serde_json::to_vec(&file).map_err(|e| DeviceInfoError::Parse(e.to_string()))?;
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| DeviceInfoError::from_io(&source, e))?;
+        }
+        // Write then rename, so a concurrent reader never sees half a file;
+        // each writer has its own temporary file next to the cache
+        static WRITES: AtomicU64 = AtomicU64::new(0);
+        let temporary = self.path.with_extension(format!(
+            "{}.{}.tmp",
+            std::process::id(),
+            WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temporary, content).map_err(|e| DeviceInfoError::from_io(&source, e))?;
+        fs::rename(&temporary, &self.path).map_err(|e| DeviceInfoError::from_io(&source, e))
+    }
+}
+
+fn sign(
+    key: &[u8],
+    binding: Option<&[u8]>,
+    entries: &BTreeMap<String, CacheEntry>,
+) -> Option<String> {
+    let binding = binding?;
+    let content = serde_json::to_vec(entries).ok()?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).ok()?;
+    // Length-prefixed, so the binding cannot run into the entries
+    mac.update(&(binding.len() as u64).to_be_bytes());
+    mac.update(binding);
+    mac.update(&content);
+    Some(hex::encode(mac.finalize().into_bytes()))
+}
+
+/// A value cheap to read live which tells machines apart: the machine-id on
+/// Linux, the `MachineGuid` on Windows and the platform serial on macOS
+///
+/// Binding the cache to it keeps a cache copied from a licensed machine
+/// from serving its hardware identifiers to another one.
+pub fn machine_binding() -> Option<Vec<u8>> {
+    let value = match whoami::platform() {
+        whoami::Platform::Windows => WindowsBuilder::new().probe(&WindowsBuilderComponents::Guid),
+        whoami::Platform::MacOS => {
+            MacOSBuilder::new().probe(&MacOSBuilderComponents::PlatformSerialNumber)
+        }
+        _ => LinuxBuilder::new().probe(&LinuxBuilderComponents::MachineId),
+    };
+    value
+        .ok()
+        .filter(|value| !value.is_empty())
+        .map(|value| value.to_string().into_bytes())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default()
+}
+
+/// `$XDG_CACHE_HOME` or `~/.cache` on Linux, `~/Library/Caches` on macOS and
+/// `%LOCALAPPDATA%` on Windows
+pub fn platform_cache_dir() -> Option<PathBuf> {
+    let non_empty = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+
+    if cfg!(target_os = "windows") {
+        non_empty("LOCALAPPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        non_empty("HOME").map(|home| PathBuf::from(home).join("Library/Caches"))
+    } else {
+        non_empty("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| non_empty("HOME").map(|home| PathBuf::from(home).join(".cache")))
+    }
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use super::*;
+    use crate::plugins::linux::fake_root::FakeRoot;
+
+    const MACHINE: Option<&[u8]> = Some(b"0123456789abcdef0123456789abcdef");
+
+    #[test]
+    fn test_cache_round_trip() {
+        let root = FakeRoot::new();
+        let path = root.path().join("app/components.json");
+
+        let cache = ComponentCache::open_with_binding(&path, b"secret", MACHINE);
+        assert_eq!(cache.get("Linux::machineId", HARDWARE_TTL), None);
+        cache.insert("Linux::machineId", "abc".into()).unwrap();
+
+        let cache = ComponentCache::open_with_binding(&path, b"secret", MACHINE);
+        assert_eq!(
+            cache.get("Linux::machineId", HARDWARE_TTL),
+            Some("abc".into())
+        );
+        assert_eq!(cache.get("Linux::machineId", Duration::ZERO), None);
+        assert_eq!(
+            ComponentCache::open_with_binding(&path, b"other key", MACHINE)
+                .get("Linux::machineId", HARDWARE_TTL),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cache_is_bound_to_the_machine() {
+        let root = FakeRoot::new();
+        let path = root.path().join("components.json");
+        ComponentCache::open_with_binding(&path, b"secret", MACHINE)
+            .insert("Linux::machineId", "abc".into())
+            .unwrap();
+
+        // The same file copied to another machine
+        assert_eq!(
+            ComponentCache::open_with_binding(&path, b"secret", Some(b"another machine"))
+                .get("Linux::machineId", HARDWARE_TTL),
+            None
+        );
+
+        let unbound = ComponentCache::open_with_binding(&path, b"secret", None);
+        assert_eq!(unbound.get("Linux::machineId", HARDWARE_TTL), None);
+        assert!(unbound.insert("Linux::machineId", "abc".into()).is_err());
+    }
+
+    #[test]
+    fn test_cache_concurrent_writes() {
+        let root = FakeRoot::new();
+        let path = root.path().join("components.json");
+
+        std::thread::scope(|scope| {
+            for writer in 0..8 {
+                let path = &path;
+                scope.spawn(move || {
+                    let cache = ComponentCache::open_with_binding(path, b"secret", MACHINE);
+                    for i in 0..20 {
+                        cache
+                            .insert(&format!("{}-{}", writer, i), "abc".into())
+                            .unwrap();
+                    }
+                });
+            }
+        });
+
+        // The last rename wins, but the file is always whole and signed
+        let cache = ComponentCache::open_with_binding(&path, b"secret", MACHINE);
+        assert!(cache.entries.lock().unwrap().len() >= 20);
+        assert_eq!(fs::read_dir(root.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_cache_detects_edits() {
+        let root = FakeRoot::new();
+        let path = root.path().join("components.json");
+        ComponentCache::open_with_binding(&path, b"secret", MACHINE)
+            .insert("Linux::machineId", "abc".into())
+            .unwrap();
+
+        let edited = fs::read_to_string(&path).unwrap().replace("abc", "xyz");
+        fs::write(&path, edited).unwrap();
+
+        assert_eq!(
+            ComponentCache::open_with_binding(&path, b"secret", MACHINE)
+                .get("Linux::machineId", HARDWARE_TTL),
+            None
+        );
+    }
+}
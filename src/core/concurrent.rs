@@ -0,0 +1,306 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::core::error::DeviceInfoError;
+use crate::core::value::ComponentValue;
+
+/// Limits of a concurrent collection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProbeLimits {
+    /// How long a single probe may run
+    pub probe_timeout: Duration,
+    /// How long the whole collection may take
+    pub total_timeout: Duration,
+    /// Size of the thread pool
+    pub threads: usize,
+}
+
+impl Default for ProbeLimits {
+    /// 10 seconds per probe, 30 seconds in total, one thread per CPU
+    fn default() -> Self {
+        Self {
+            probe_timeout: Duration::from_secs(10),
+            total_timeout: Duration::from_secs(30),
+            threads: thread::available_parallelism()
+                .map(|threads| threads.get())
+                .unwrap_or(4),
+        }
+    }
+}
+
+enum Event<K> {
+    Started(K),
+    Finished(K, Result<ComponentValue, DeviceInfoError>),
+}
+
+/// Run `probe` for every name on a thread pool
+///
+/// A probe still running after [`ProbeLimits::probe_timeout`], and every
+/// probe not finished after [`ProbeLimits::total_timeout`], fails with
+/// [`DeviceInfoError::TimedOut`]. The call returns then, without waiting for
+/// them: a hung probe (WMI, a stale mount, ...) keeps its worker thread
+/// until it returns, and its result is discarded.
+pub fn probe_concurrently<K, F>(
+    names: Vec<K>,
+    probe: F,
+    limits: ProbeLimits,
+) -> Vec<(K, Result<ComponentValue, DeviceInfoError>)>
+where
+    K: Clone + Hash + Eq + fmt::Display + Send + 'static,
+    F: Fn(&K) -> Result<ComponentValue, DeviceInfoError> + Send + Sync + 'static,
+{
+    let names = deduplicate(names);
+    let deadline = Instant::now() + limits.total_timeout;
+    let (job_sender, job_receiver) = mpsc::channel::<K>();
+    let (event_sender, event_receiver) = mpsc::channel::<Event<K>>();
+    let job_receiver = Arc::new(Mutex::new(job_receiver));
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let probe = Arc::new(probe);
+
+    for name in names.iter() {
+        job_sender.send(name.clone()).unwrap();
+    }
+    drop(job_sender);
+
+    for _ in 0..limits.threads.clamp(1, names.len().max(1)) {
+        let job_receiver = Arc::clone(&job_receiver);
+        let event_sender = event_sender.clone();
+        let cancelled = Arc::clone(&cancelled);
+        let probe = Arc::clone(&probe);
+        thread::spawn(move || loop {
+            let name = match job_receiver.lock().unwrap().recv() {
+                Ok(name) => name,
+                Err(_) => break,
+            };
+            if cancelled.load(Ordering::Relaxed)
+                || event_sender.send(Event::Started(name.clone())).is_err()
+            {
+                break;
+            }
+            let result = probe(&name);
+            if event_sender.send(Event::Finished(name, result)).is_err() {
+                break;
+            }
+        });
+    }
+    drop(event_sender);
+
+    let mut results: HashMap<K, Result<ComponentValue, DeviceInfoError>> = HashMap::new();
+    let mut running: HashMap<K, Instant> = HashMap::new();
+    while results.len() < names.len() {
+        let next_deadline = running
+            .values()
+            .map(|started| *started + limits.probe_timeout)
+            .chain(std::iter::once(deadline))
+            .min()
+            .unwrap();
+        let event =
+            event_receiver.recv_timeout(next_deadline.saturating_duration_since(Instant::now()));
+
+        match event {
+            Ok(Event::Started(name)) => {
+                running.insert(name, Instant::now());
+            }
+            Ok(Event::Finished(name, result)) => {
+                if running.remove(&name).is_some() {
+                    results.insert(name, result);
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+
+        let now = Instant::now();
+        let expired: Vec<K> = running
+            .iter()
+            .filter(|(_, started)| now >= **started + limits.probe_timeout)
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in expired {
+            running.remove(&name);
+            let e = timed_out(&name, limits.probe_timeout);
+            results.insert(name, Err(e));
+        }
+        if now >= deadline {
+            break;
+        }
+    }
+    cancelled.store(true, Ordering::Relaxed);
+
+    in_order(names, results, limits)
+}
+
+/// [`probe_concurrently`] for async applications, on the current tokio
+/// runtime
+///
+/// Each probe is a task, so [`ProbeLimits::threads`] is not used: blocking
+/// probes are expected to run on the blocking pool of the runtime. Probes
+/// are timed out like in [`probe_concurrently`]; their tasks are aborted,
+/// but a blocking probe keeps its thread until it returns.
+#[cfg(feature = "async")]
+pub async fn probe_concurrently_async<K, F, Fut>(
+    names: Vec<K>,
+    probe: F,
+    limits: ProbeLimits,
+) -> Vec<(K, Result<ComponentValue, DeviceInfoError>)>
+where
+    K: Clone + Hash + Eq + fmt::Display + Send + 'static,
+    F: Fn(K) -> Fut,
+    Fut: std::future::Future<Output = Result<ComponentValue, DeviceInfoError>> + Send + 'static,
+{
+    let names = deduplicate(names);
+    let deadline = tokio::time::Instant::now() + limits.total_timeout;
+    let mut tasks = tokio::task::JoinSet::new();
+    for name in names.iter() {
+        let probe = probe(name.clone());
+        let name = name.clone();
+        tasks.spawn(async move {
+            let result = match tokio::time::timeout(limits.probe_timeout, probe).await {
+                Ok(result) => result,
+                Err(_) => Err(timed_out(&name, limits.probe_timeout)),
+            };
+            (name, result)
+        });
+    }
+
+    let mut results = HashMap::new();
+    while let Ok(Some(joined)) = tokio::time::timeout_at(deadline, tasks.join_next()).await {
+        // A panicking probe is reported as timed out, like a hung one
+        if let Ok((name, result)) = joined {
+            results.insert(name, result);
+        }
+    }
+    tasks.abort_all();
+
+    in_order(names, results, limits)
+}
+
+fn deduplicate<K: Clone + Hash + Eq>(names: Vec<K>) -> Vec<K> {
+    let mut names = names;
+    let mut seen = HashSet::new();
+    names.retain(|name| seen.insert(name.clone()));
+    names
+}
+
+fn timed_out<K: fmt::Display>(name: &K, timeout: Duration) -> DeviceInfoError {
+    DeviceInfoError::TimedOut(format!("{} did not finish within {:?}", name, timeout))
+}
+
+/// The results in the order of `names`, those missing timed out
+fn in_order<K: Hash + Eq + fmt::Display>(
+    names: Vec<K>,
+    mut results: HashMap<K, Result<ComponentValue, DeviceInfoError>>,
+    limits: ProbeLimits,
+) -> Vec<(K, Result<ComponentValue, DeviceInfoError>)> {
+    names
+        .into_iter()
+        .map(|name| {
+            let result = results
+                .remove(&name)
+                .unwrap_or_else(|| Err(timed_out(&name, limits.total_timeout)));
+            (name, result)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(probe_timeout: u64, total_timeout: u64, threads: usize) -> ProbeLimits {
+        ProbeLimits {
+            probe_timeout: Duration::from_millis(probe_timeout),
+            total_timeout: Duration::from_millis(total_timeout),
+            threads,
+        }
+    }
+
+    #[test]
+    fn test_probe_concurrently() {
+        let started = Instant::now();
+        let results = probe_concurrently(
+            vec![1, 2, 3, 4],
+            |name: &u8| {
+                thread::sleep(Duration::from_millis(100));
+                match *name {
+                    4 => Err(DeviceInfoError::NotFound("4".to_string())),
+                    name => Ok(name.to_string().into()),
+                }
+            },
+            limits(1_000, 5_000, 4),
+        );
+
+        assert!(started.elapsed() < Duration::from_millis(350));
+        assert_eq!(results[0], (1, Ok("1".into())));
+        assert_eq!(results[2], (3, Ok("3".into())));
+        assert_eq!(
+            results[3],
+            (4, Err(DeviceInfoError::NotFound("4".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_probe_timeouts() {
+        let started = Instant::now();
+        let results = probe_concurrently(
+            vec![1, 2, 3],
+            |name: &u8| {
+                if *name == 1 {
+                    thread::sleep(Duration::from_secs(5));
+                }
+                Ok(name.to_string().into())
+            },
+            limits(100, 5_000, 2),
+        );
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(matches!(results[0].1, Err(DeviceInfoError::TimedOut(_))));
+        assert_eq!(results[1].1, Ok("2".into()));
+        assert_eq!(results[2].1, Ok("3".into()));
+
+        // A single worker stuck on the first probe never gets to the others
+        let results = probe_concurrently(
+            vec![1, 2],
+            |_: &u8| {
+                thread::sleep(Duration::from_secs(5));
+                Ok(ComponentValue::default())
+            },
+            limits(5_000, 100, 1),
+        );
+        assert!(results
+            .iter()
+            .all(|(_, result)| matches!(result, Err(DeviceInfoError::TimedOut(_)))));
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn test_probe_concurrently_async() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let started = Instant::now();
+        let results = runtime.block_on(probe_concurrently_async(
+            vec![1, 2, 2, 3],
+            |name: u8| async move {
+                match name {
+                    1 => tokio::time::sleep(Duration::from_secs(5)).await,
+                    _ => tokio::time::sleep(Duration::from_millis(50)).await,
+                }
+                Ok(name.to_string().into())
+            },
+            limits(100, 5_000, 1),
+        ));
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[0].1, Err(DeviceInfoError::TimedOut(_))));
+        assert_eq!(results[1], (2, Ok("2".into())));
+        assert_eq!(results[2], (3, Ok("3".into())));
+    }
+}
@@ -0,0 +1,414 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use itertools::Itertools;
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use crate::core::error::DeviceInfoError;
+use crate::core::internal::IDeviceInfoBuilder;
+use crate::core::value::ComponentValue;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha1,
+    Md5,
+    /// HMAC-SHA256 with a secret key, so the ID cannot be recomputed from
+    /// the components alone
+    HmacSha256(Vec<u8>),
+}
+
+impl HashAlgorithm {
+    /// `sha256`, `sha1` or `md5`; HMAC needs a key, see
+    /// [`HashAlgorithm::HmacSha256`]
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "sha256" => Some(HashAlgorithm::Sha256),
+            "sha1" => Some(HashAlgorithm::Sha1),
+            "md5" => Some(HashAlgorithm::Md5),
+            _ => None,
+        }
+    }
+
+    fn digest(&self, data: &[u8]) -> Vec<u8> {
+        match *self {
+            HashAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+            HashAlgorithm::Sha1 => Sha1::digest(data).to_vec(),
+            HashAlgorithm::Md5 => Md5::digest(data).to_vec(),
+            HashAlgorithm::HmacSha256(ref key) => {
+                // HMAC accepts keys of any length
+                let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+                mac.update(data);
+                mac.finalize().into_bytes().to_vec()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Hex,
+    /// Crockford's Base32, upper case and without padding
+    Base32Crockford,
+    /// URL-safe base64 without padding
+    Base64Url,
+}
+
+impl Encoding {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "hex" => Some(Encoding::Hex),
+            "base32" => Some(Encoding::Base32Crockford),
+            "base64url" => Some(Encoding::Base64Url),
+            _ => None,
+        }
+    }
+
+    fn encode(&self, data: &[u8]) -> String {
+        match *self {
+            Encoding::Hex => hex::encode(data),
+            Encoding::Base32Crockford => base32_crockford(data),
+            Encoding::Base64Url => general_purpose::URL_SAFE_NO_PAD.encode(data),
+        }
+    }
+}
+
+const CROCKFORD_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+fn base32_crockford(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity((data.len() * 8).div_ceil(5));
+    let mut buffer: u16 = 0;
+    let mut bits = 0;
+    for byte in data {
+        buffer = (buffer << 8) | *byte as u16;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(CROCKFORD_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(CROCKFORD_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    encoded
+}
+
+/// Turn the components of a builder into a single fixed-length device ID,
+/// like the `DeviceId` C# library
+///
+/// The components are canonicalized as a JSON object sorted by key, so the
+/// ID does not depend on the order they were added in.
+pub struct DeviceIdFormatter {
+    algorithm: HashAlgorithm,
+    encoding: Encoding,
+    components: Vec<String>,
+    fallbacks: Vec<String>,
+}
+
+impl DeviceIdFormatter {
+    /// SHA-256, hex encoded, over every component
+    pub fn new() -> Self {
+        Self {
+            algorithm: HashAlgorithm::Sha256,
+            encoding: Encoding::Hex,
+            components: vec![],
+            fallbacks: vec![],
+        }
+    }
+
+    pub fn set_algorithm(&mut self, algorithm: HashAlgorithm) -> &mut Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    pub fn set_encoding(&mut self, encoding: Encoding) -> &mut Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Only use the components with these names (e.g. `Linux::machineId`)
+    pub fn set_components<I, S>(&mut self, components: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.components = components.into_iter().map(|name| name.into()).collect();
+        self
+    }
+
+    /// Components used, in order, in place of selected ones which are
+    /// missing or hold a placeholder, see [`Self::format_builder`]
+    pub fn set_fallback_components<I, S>(&mut self, components: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.fallbacks = components.into_iter().map(|name| name.into()).collect();
+        self
+    }
+
+    /// The canonical form of the selected components, which gets hashed
+    ///
+    /// Every selected component must be present, otherwise two devices
+    /// missing different components could share an ID.
+    pub fn canonicalize<KT>(
+        &self,
+        components: &HashMap<KT, ComponentValue>,
+    ) -> Result<String, DeviceInfoError>
+    where
+        KT: Hash + Eq + fmt::Display,
+    {
+        let all: BTreeMap<String, &ComponentValue> = components
+            .iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        self.canonicalize_map(all, &self.components)
+    }
+
+    /// `all` as JSON, restricted to `selected` unless it is empty
+    fn canonicalize_map(
+        &self,
+        all: BTreeMap<String, &ComponentValue>,
+        selected: &[String],
+    ) -> Result<String, DeviceInfoError> {
+        let selected = match selected.is_empty() {
+            true => all,
+            false => selected
+                .iter()
+                .map(|name| match all.get(name) {
+                    Some(value) => Ok((name.to_owned(), *value)),
+                    None => Err(DeviceInfoError::NotFound(format!("Component '{}'", name))),
+                })
+                .collect::<Result<_, _>>()?,
+        };
+
+        serde_json::to_string(&selected).map_err(|e| DeviceInfoError::Parse(e.to_string()))
+    }
+
+    fn hash(&self, canonical: &str) -> String {
+        self.encoding
+            .encode(&self.algorithm.digest(canonical.as_bytes()))
+    }
+
+    pub fn format<KT>(
+        &self,
+        components: &HashMap<KT, ComponentValue>,
+    ) -> Result<String, DeviceInfoError>
+    where
+        KT: Hash + Eq + fmt::Display,
+    {
+        Ok(self.hash(&self.canonicalize(components)?))
+    }
+
+    /// [`Self::format`] over the reliable components of `builder`
+    ///
+    /// Components holding a vendor placeholder, or which failed to be
+    /// collected, are left out. A selected component which is missing or
+    /// left out is replaced by the next usable fallback component. Without
+    /// a selection, every component which is not a placeholder is selected,
+    /// so a component unreadable by the current user fails instead of
+    /// silently changing the ID.
+    pub fn format_builder<KT, B>(&self, builder: &B) -> Result<String, DeviceInfoError>
+    where
+        KT: Clone + Hash + Eq + fmt::Display + Ord,
+        B: IDeviceInfoBuilder<KT>,
+    {
+        let reliable: BTreeMap<String, &ComponentValue> = builder
+            .get_components()
+            .iter()
+            .filter(|(name, _)| !builder.is_unreliable(name) && !builder.is_unavailable(name))
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+
+        let wanted = match self.components.is_empty() {
+            true => builder
+                .get_components()
+                .keys()
+                .filter(|name| !builder.is_unreliable(name))
+                .map(|name| name.to_string())
+                .sorted()
+                .collect(),
+            false => self.components.to_owned(),
+        };
+
+        let mut fallbacks = self
+            .fallbacks
+            .iter()
+            .filter(|name| reliable.contains_key(*name) && !wanted.contains(name));
+        let mut selected = vec![];
+        for name in &wanted {
+            match reliable.contains_key(name) {
+                true => selected.push(name.to_owned()),
+                false => match fallbacks.next() {
+                    Some(fallback) => selected.push(fallback.to_owned()),
+                    None => {
+                        return Err(DeviceInfoError::NotFound(format!(
+                            "Reliable component '{}' or a fallback",
+                            name
+                        )))
+                    }
+                },
+            }
+        }
+
+        Ok(self.hash(&self.canonicalize_map(reliable, &selected)?))
+    }
+}
+
+impl Default for DeviceIdFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn components() -> HashMap<String, ComponentValue> {
+        HashMap::from([
+            ("Linux::machineId".to_string(), "abc".into()),
+            ("MACAddresses".to_string(), ComponentValue::list(["b", "a"])),
+        ])
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let formatter = DeviceIdFormatter::new();
+        assert_eq!(
+            formatter.canonicalize(&components()).unwrap(),
+            r#"{"Linux::machineId":"abc","MACAddresses":["a","b"]}"#
+        );
+
+        let mut formatter = DeviceIdFormatter::new();
+        formatter.set_components(["Linux::machineId"]);
+        assert_eq!(
+            formatter.canonicalize(&components()).unwrap(),
+            r#"{"Linux::machineId":"abc"}"#
+        );
+
+        formatter.set_components(["Linux::productUuid"]);
+        assert!(matches!(
+            formatter.canonicalize(&components()),
+            Err(DeviceInfoError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_format() {
+        let mut formatter = DeviceIdFormatter::new();
+        let sha256 = formatter.format(&components()).unwrap();
+        assert_eq!(sha256.len(), 64);
+        assert_eq!(
+            sha256,
+            hex::encode(Sha256::digest(
+                r#"{"Linux::machineId":"abc","MACAddresses":["a","b"]}"#
+            ))
+        );
+
+        formatter.set_algorithm(HashAlgorithm::Md5);
+        assert_eq!(formatter.format(&components()).unwrap().len(), 32);
+
+        formatter
+            .set_algorithm(HashAlgorithm::HmacSha256(b"secret".to_vec()))
+            .set_encoding(Encoding::Base64Url);
+        let hmac = formatter.format(&components()).unwrap();
+        assert_eq!(hmac.len(), 43);
+        assert_ne!(
+            hmac,
+            general_purpose::URL_SAFE_NO_PAD.encode(hex::decode(sha256).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_base32_crockford() {
+        assert_eq!(base32_crockford(b""), "");
+        assert_eq!(base32_crockford(b"f"), "CR");
+        assert_eq!(base32_crockford(b"foobar"), "CSQPYRK1E8");
+        assert_eq!(base32_crockford(&[0xff; 5]), "ZZZZZZZZ");
+    }
+
+    #[test]
+    fn test_format_builder_falls_back() {
+        use crate::core::builder::{MainBuilderComponents, MainDeviceInfoBuilder};
+        use crate::plugins::linux::plugin::LinuxBuilderComponents;
+
+        let linux = |component| MainBuilderComponents::LinuxBuilderComponents(component);
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .merge_component(
+                &linux(LinuxBuilderComponents::BoardSerialNumber),
+                "To Be Filled By O.E.M.",
+            )
+            .merge_component(
+                &linux(LinuxBuilderComponents::SystemDriveSerialNumber),
+                "S3Z9NB0K",
+            )
+            .merge_component(&linux(LinuxBuilderComponents::MachineId), "abc");
+        assert!(builder.is_unreliable(&linux(LinuxBuilderComponents::BoardSerialNumber)));
+
+        let mut formatter = DeviceIdFormatter::new();
+        formatter
+            .set_components(["Linux::boardSerialNumber", "Linux::machineId"])
+            .set_fallback_components([
+                "Linux::boardSerialNumber",
+                "Linux::systemDriveSerialNumber",
+            ]);
+        assert_eq!(
+            formatter.format_builder(&builder).unwrap(),
+            hex::encode(Sha256::digest(
+                r#"{"Linux::machineId":"abc","Linux::systemDriveSerialNumber":"S3Z9NB0K"}"#
+            ))
+        );
+
+        formatter.set_fallback_components(Vec::<String>::new());
+        assert!(matches!(
+            formatter.format_builder(&builder),
+            Err(DeviceInfoError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_format_builder_skips_failures() {
+        use crate::core::builder::{MainBuilderComponents, MainDeviceInfoBuilder};
+        use crate::plugins::linux::plugin::LinuxBuilderComponents;
+
+        // The product UUID is only readable by root
+        let linux = |component| MainBuilderComponents::LinuxBuilderComponents(component);
+        let mut builder = MainDeviceInfoBuilder::new();
+        builder
+            .add_probe_result(
+                &linux(LinuxBuilderComponents::ProductUuid),
+                Err(DeviceInfoError::PermissionDenied(
+                    "/sys/class/dmi/id/product_uuid".to_string(),
+                )),
+            )
+            .merge_component(&linux(LinuxBuilderComponents::MachineId), "abc");
+
+        let mut formatter = DeviceIdFormatter::new();
+        formatter.set_components(["Linux::productUuid"]);
+        assert!(matches!(
+            formatter.format_builder(&builder),
+            Err(DeviceInfoError::NotFound(_))
+        ));
+
+        formatter.set_fallback_components(["Linux::machineId"]);
+        assert_eq!(
+            formatter.format_builder(&builder).unwrap(),
+            hex::encode(Sha256::digest(r#"{"Linux::machineId":"abc"}"#))
+        );
+
+        // Every component is selected by default, failures included
+        assert!(matches!(
+            DeviceIdFormatter::new().format_builder(&builder),
+            Err(DeviceInfoError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,98 @@
+use std::{fmt, io};
+
+use serde::{Deserialize, Serialize};
+
+/// Why a component could not be collected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeviceInfoError {
+    /// The component does not exist on this platform
+    Unsupported(String),
+    /// The source exists but is not readable by the current user, e.g.
+    /// root-only DMI serials
+    PermissionDenied(String),
+    /// The source (file, registry key, WMI instance, ...) does not exist
+    NotFound(String),
+    /// The probe itself failed: WMI, COM or a child process returned an error
+    ProbeFailed(String),
+    /// The source was read but its content could not be understood
+    Parse(String),
+    /// The component was already added, see `DuplicatePolicy`
+    DuplicateComponent(String),
+    /// The value is a known placeholder ("To Be Filled By O.E.M.", all-zero
+    /// UUID, ...) shared by many devices, see `PlaceholderPolicy`
+    Placeholder(String),
+    /// The probe did not finish in time, see `ProbeLimits`
+    TimedOut(String),
+}
+
+impl DeviceInfoError {
+    /// Map an I/O error on `source` to the matching variant
+    pub fn from_io(source: &str, error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::NotFound | io::ErrorKind::PermissionDenied => {
+                Self::from_io_kind(error.kind(), source.to_string())
+            }
+            kind => Self::from_io_kind(kind, format!("{}: {}", source, error)),
+        }
+    }
+
+    fn from_io_kind(kind: io::ErrorKind, message: String) -> Self {
+        match kind {
+            io::ErrorKind::NotFound => DeviceInfoError::NotFound(message),
+            io::ErrorKind::PermissionDenied => DeviceInfoError::PermissionDenied(message),
+            io::ErrorKind::InvalidData => DeviceInfoError::Parse(message),
+            _ => DeviceInfoError::ProbeFailed(message),
+        }
+    }
+}
+
+impl fmt::Display for DeviceInfoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeviceInfoError::Unsupported(message) => write!(f, "Unsupported: {}", message),
+            DeviceInfoError::PermissionDenied(message) => {
+                write!(f, "Permission denied: {}", message)
+            }
+            DeviceInfoError::NotFound(message) => write!(f, "Not found: {}", message),
+            DeviceInfoError::ProbeFailed(message) => write!(f, "Probe failed: {}", message),
+            DeviceInfoError::Parse(message) => write!(f, "Parse error: {}", message),
+            DeviceInfoError::DuplicateComponent(name) => {
+                write!(f, "Component '{}' already exists", name)
+            }
+            DeviceInfoError::Placeholder(value) => write!(f, "Placeholder value: {}", value),
+            DeviceInfoError::TimedOut(message) => write!(f, "Timed out: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for DeviceInfoError {}
+
+impl From<io::Error> for DeviceInfoError {
+    fn from(error: io::Error) -> Self {
+        Self::from_io_kind(error.kind(), error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_io() {
+        let error = DeviceInfoError::from_io(
+            "/sys/class/dmi/id/board_serial",
+            io::Error::from(io::ErrorKind::PermissionDenied),
+        );
+        assert!(matches!(error, DeviceInfoError::PermissionDenied(_)));
+        assert!(error.to_string().contains("board_serial"));
+
+        assert!(matches!(
+            DeviceInfoError::from(io::Error::from(io::ErrorKind::NotFound)),
+            DeviceInfoError::NotFound(_)
+        ));
+        assert!(matches!(
+            DeviceInfoError::from(io::Error::from(io::ErrorKind::TimedOut)),
+            DeviceInfoError::ProbeFailed(_)
+        ));
+    }
+}
@@ -1,52 +1,280 @@
 use itertools::Itertools;
 use std::{collections::HashMap, fmt, hash::Hash};
 
+use crate::core::error::DeviceInfoError;
+use crate::core::placeholder::{drop_placeholders, placeholders, PlaceholderPolicy};
+use crate::core::provenance::{Confidence, Provenance};
+use crate::core::value::ComponentValue;
+
+/// What to do when a component is added a second time
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Keep the first value and report the conflict
+    #[default]
+    Error,
+    /// Silently keep the first value
+    KeepFirst,
+    /// Replace the value
+    Overwrite,
+    /// Merge the values into a list
+    Append,
+}
+
 pub trait IDeviceInfoBuilder<KT>
 where
-    KT: Clone + Copy + Hash + Eq + fmt::Display + Ord,
+    KT: Clone + Hash + Eq + fmt::Display + Ord,
 {
-    fn get_components(&self) -> &HashMap<KT, String>;
-    fn get_components_mut(&mut self) -> &mut HashMap<KT, String>;
+    fn get_components(&self) -> &HashMap<KT, ComponentValue>;
+    fn get_components_mut(&mut self) -> &mut HashMap<KT, ComponentValue>;
+    fn get_failures(&self) -> &HashMap<KT, DeviceInfoError>;
+    fn get_failures_mut(&mut self) -> &mut HashMap<KT, DeviceInfoError>;
+    fn get_provenance(&self) -> &HashMap<KT, Provenance>;
+    fn get_provenance_mut(&mut self) -> &mut HashMap<KT, Provenance>;
+    fn get_duplicate_policy(&self) -> DuplicatePolicy;
+    fn set_duplicate_policy(&mut self, policy: DuplicatePolicy) -> &mut Self;
+    fn get_placeholder_policy(&self) -> PlaceholderPolicy;
+    fn set_placeholder_policy(&mut self, policy: PlaceholderPolicy) -> &mut Self;
+
+    /// Bring a value into canonical form before it is stored, see
+    /// [`Normalizer`](crate::core::normalize::Normalizer)
+    fn normalize(&self, _name: &KT, value: ComponentValue) -> ComponentValue {
+        value
+    }
+
+    /// Whether the component is a hardware identifier, checked for vendor
+    /// placeholders with the [`PlaceholderPolicy`] of the builder
+    fn is_identifier(&self, _name: &KT) -> bool {
+        false
+    }
+
+    /// Where the value of the component is read, for its [`Provenance`]
+    fn source(&self, name: &KT) -> String {
+        format!("{} (unknown source)", name)
+    }
+
+    /// How far a value read from [`Self::source`] can be trusted
+    fn confidence(&self, _name: &KT) -> Confidence {
+        Confidence::Medium
+    }
+
+    /// Whether the value of the component is a known placeholder
+    fn is_unreliable(&self, name: &KT) -> bool {
+        matches!(
+            self.get_failures().get(name),
+            Some(DeviceInfoError::Placeholder(_))
+        )
+    }
+
+    /// Whether the component failed to be collected, its value being empty
+    ///
+    /// Placeholders and conflicts leave a usable value, see
+    /// [`Self::is_unreliable`] and [`Self::merge_component`].
+    fn is_unavailable(&self, name: &KT) -> bool {
+        !matches!(
+            self.get_failures().get(name),
+            None | Some(DeviceInfoError::Placeholder(_))
+                | Some(DeviceInfoError::DuplicateComponent(_))
+        )
+    }
+
+    /// Add a component value, resolving an existing one with the
+    /// [`DuplicatePolicy`] of the builder
+    ///
+    /// The value is normalized first, and placeholders are handled with the
+    /// [`PlaceholderPolicy`]. Only [`DuplicatePolicy::Error`] fails, leaving
+    /// the first value in place.
+    fn add_component<V>(&mut self, name: &KT, value: V) -> Result<&mut Self, DeviceInfoError>
+    where
+        V: Into<ComponentValue>,
+    {
+        let mut value = self.normalize(name, value.into());
+        let mut placeholder = None;
+        if self.is_identifier(name) && self.get_placeholder_policy() != PlaceholderPolicy::Keep {
+            let found = placeholders(&value).join(",");
+            if !found.is_empty() {
+                placeholder = Some(DeviceInfoError::Placeholder(found));
+                if self.get_placeholder_policy() == PlaceholderPolicy::Drop {
+                    value = drop_placeholders(value);
+                }
+            }
+        }
+
+        let policy = self.get_duplicate_policy();
+        match (self.get_components_mut().get_mut(name), policy) {
+            (None, _) => {
+                self.get_components_mut().insert(name.clone(), value);
+            }
+            (Some(_), DuplicatePolicy::Error) => {
+                return Err(DeviceInfoError::DuplicateComponent(name.to_string()));
+            }
+            (Some(_), DuplicatePolicy::KeepFirst) => return Ok(self),
+            (Some(existing), DuplicatePolicy::Overwrite) => *existing = value,
+            (Some(existing), DuplicatePolicy::Append) => existing.append(value),
+        }
 
-    fn add_component(&mut self, name: &KT, value: &str) -> &mut Self {
-        let components = self.get_components_mut();
-        if components.get(name).is_some() {
-            panic!("Component with name '{}' already exists", name);
+        // The flag follows the stored value: an appended list keeps the
+        // placeholders it already holds, a replaced value does not, and a
+        // good value clears the failure of the one before
+        let stale = match self.get_failures().get(name) {
+            Some(DeviceInfoError::Placeholder(_)) => policy == DuplicatePolicy::Overwrite,
+            Some(_) => !self.get_components()[name].is_empty(),
+            None => false,
+        };
+        if stale {
+            self.get_failures_mut().remove(name);
+        }
+        if let Some(e) = placeholder {
+            self.get_failures_mut().insert(name.clone(), e);
+        }
+        Ok(self)
+    }
+
+    /// [`Self::add_component`], recording a conflict in [`Self::get_failures`]
+    /// instead of failing, so calls can be chained
+    ///
+    /// The conflict does not replace an earlier failure of the component,
+    /// and does not make its value unavailable.
+    fn merge_component<V>(&mut self, name: &KT, value: V) -> &mut Self
+    where
+        V: Into<ComponentValue>,
+    {
+        if let Err(e) = self.add_component(name, value) {
+            self.get_failures_mut().entry(name.clone()).or_insert(e);
         }
-        components.insert(*name, value.to_string());
         self
     }
 
-    fn extend_components(&mut self, components: &HashMap<KT, String>) -> &mut Self {
+    /// Read the value of a component without adding it
+    ///
+    /// Builders override this for the components they know how to collect;
+    /// the `add_*` methods are shorthands for [`Self::add_probed`].
+    fn probe(&self, name: &KT) -> Result<ComponentValue, DeviceInfoError> {
+        Err(DeviceInfoError::Unsupported(format!(
+            "Component '{}' cannot be probed by this builder",
+            name
+        )))
+    }
+
+    /// Probe a component and add it, or hand the error back to the caller
+    /// without touching the collected components
+    fn try_add(&mut self, name: &KT) -> Result<&mut Self, DeviceInfoError> {
+        let (value, provenance) =
+            Provenance::trace(self.source(name), self.confidence(name), || {
+                self.probe(name)
+            });
+        self.add_component(name, value?)?;
+        Ok(self.add_provenance(name, provenance))
+    }
+
+    /// Add a component, or record why it failed and keep going
+    ///
+    /// A failed component still gets an empty value, so machine codes built
+    /// from the components keep the same shape; the error is kept in
+    /// [`Self::get_failures`].
+    fn add_probed(&mut self, name: &KT) -> &mut Self {
+        let (result, provenance) =
+            Provenance::trace(self.source(name), self.confidence(name), || {
+                self.probe(name)
+            });
+        self.add_probe_result(name, result)
+            .add_provenance(name, provenance)
+    }
+
+    /// The second half of [`Self::add_probed`], for values probed elsewhere,
+    /// e.g. on another thread
+    fn add_probe_result(
+        &mut self,
+        name: &KT,
+        result: Result<ComponentValue, DeviceInfoError>,
+    ) -> &mut Self {
+        match result {
+            Ok(value) => self.merge_component(name, value),
+            Err(e) => {
+                let overwrite = self.get_duplicate_policy() == DuplicatePolicy::Overwrite;
+                self.merge_component(name, ComponentValue::default());
+                // The error belongs to the stored value only if it is the
+                // empty one, and does not replace the error of a kept value
+                let earlier = !matches!(
+                    self.get_failures().get(name),
+                    None | Some(DeviceInfoError::DuplicateComponent(_))
+                );
+                if self.get_components()[name].is_empty() && (overwrite || !earlier) {
+                    self.get_failures_mut().insert(name.clone(), e);
+                }
+                self
+            }
+        }
+    }
+
+    /// Record where the value of a component came from, once it is added
+    ///
+    /// A component which failed or holds a placeholder gets
+    /// [`Confidence::Low`].
+    fn add_provenance(&mut self, name: &KT, provenance: Provenance) -> &mut Self {
+        let mut provenance = provenance;
+        if self.is_unavailable(name) || self.is_unreliable(name) {
+            provenance.confidence = Confidence::Low;
+        }
+        self.get_provenance_mut().insert(name.clone(), provenance);
+        self
+    }
+
+    fn extend_components(&mut self, components: &HashMap<KT, ComponentValue>) -> &mut Self {
         for component in components {
-            self.add_component(component.0, component.1);
+            self.merge_component(component.0, component.1.to_owned());
         }
         self
     }
+
+    fn extend_failures(&mut self, failures: &HashMap<KT, DeviceInfoError>) -> &mut Self {
+        self.get_failures_mut().extend(
+            failures
+                .iter()
+                .map(|(name, e)| (name.clone(), e.to_owned())),
+        );
+        self
+    }
+
+    fn extend_provenance(&mut self, provenance: &HashMap<KT, Provenance>) -> &mut Self {
+        self.get_provenance_mut().extend(
+            provenance
+                .iter()
+                .map(|(name, provenance)| (name.clone(), provenance.to_owned())),
+        );
+        self
+    }
 }
 
 #[derive(Debug)]
 pub struct BaseDeviceInfoBuilder<KT>
 where
-    KT: Clone + Copy + Hash + Eq + fmt::Display + Ord,
+    KT: Clone + Hash + Eq + fmt::Display + Ord,
 {
-    pub components: HashMap<KT, String>,
+    pub components: HashMap<KT, ComponentValue>,
+    pub failures: HashMap<KT, DeviceInfoError>,
+    pub provenance: HashMap<KT, Provenance>,
+    pub duplicate_policy: DuplicatePolicy,
+    pub placeholder_policy: PlaceholderPolicy,
 }
 
 impl<KT> BaseDeviceInfoBuilder<KT>
 where
-    KT: Clone + Copy + Hash + Eq + fmt::Display + Ord,
+    KT: Clone + Hash + Eq + fmt::Display + Ord,
 {
     pub fn new() -> Self {
         Self {
             components: HashMap::new(),
+            failures: HashMap::new(),
+            provenance: HashMap::new(),
+            duplicate_policy: DuplicatePolicy::default(),
+            placeholder_policy: PlaceholderPolicy::default(),
         }
     }
 }
 
 impl<KT> Default for BaseDeviceInfoBuilder<KT>
 where
-    KT: Clone + Copy + Hash + Eq + fmt::Display + Ord,
+    KT: Clone + Hash + Eq + fmt::Display + Ord,
 {
     fn default() -> Self {
         Self::new()
@@ -55,7 +283,7 @@ where
 
 impl<KT> fmt::Display for BaseDeviceInfoBuilder<KT>
 where
-    KT: Clone + Copy + Hash + Eq + fmt::Display + Ord,
+    KT: Clone + Hash + Eq + fmt::Display + Ord,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(
@@ -63,7 +291,16 @@ where
                 .get_components()
                 .iter()
                 .sorted_by_key(|el| el.0)
-                .map(|component| format!("{}: {}", component.0, component.1))
+                .map(|(name, value)| match self.failures.get(name) {
+                    Some(e @ DeviceInfoError::Placeholder(_)) => {
+                        format!("{}: {} <unreliable: {}>", name, value, e)
+                    }
+                    Some(e @ DeviceInfoError::DuplicateComponent(_)) => {
+                        format!("{}: {} <conflict: {}>", name, value, e)
+                    }
+                    Some(e) => format!("{}: <unavailable: {}>", name, e),
+                    None => format!("{}: {}", name, value),
+                })
                 .collect::<Vec<String>>()
                 .join("\n"),
         )
@@ -72,13 +309,138 @@ where
 
 impl<KT> IDeviceInfoBuilder<KT> for BaseDeviceInfoBuilder<KT>
 where
-    KT: Clone + Copy + Hash + Eq + fmt::Display + Ord,
+    KT: Clone + Hash + Eq + fmt::Display + Ord,
 {
-    fn get_components(&self) -> &HashMap<KT, String> {
+    fn get_components(&self) -> &HashMap<KT, ComponentValue> {
         &self.components
     }
 
-    fn get_components_mut(&mut self) -> &mut HashMap<KT, String> {
+    fn get_components_mut(&mut self) -> &mut HashMap<KT, ComponentValue> {
         &mut self.components
     }
+
+    fn get_failures(&self) -> &HashMap<KT, DeviceInfoError> {
+        &self.failures
+    }
+
+    fn get_failures_mut(&mut self) -> &mut HashMap<KT, DeviceInfoError> {
+        &mut self.failures
+    }
+
+    fn get_provenance(&self) -> &HashMap<KT, Provenance> {
+        &self.provenance
+    }
+
+    fn get_provenance_mut(&mut self) -> &mut HashMap<KT, Provenance> {
+        &mut self.provenance
+    }
+
+    fn get_duplicate_policy(&self) -> DuplicatePolicy {
+        self.duplicate_policy
+    }
+
+    fn set_duplicate_policy(&mut self, policy: DuplicatePolicy) -> &mut Self {
+        self.duplicate_policy = policy;
+        self
+    }
+
+    fn get_placeholder_policy(&self) -> PlaceholderPolicy {
+        self.placeholder_policy
+    }
+
+    fn set_placeholder_policy(&mut self, policy: PlaceholderPolicy) -> &mut Self {
+        self.placeholder_policy = policy;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builder_with(policy: DuplicatePolicy) -> BaseDeviceInfoBuilder<u8> {
+        let mut builder = BaseDeviceInfoBuilder::<u8>::new();
+        builder.set_duplicate_policy(policy);
+        builder.add_component(&1, "first").unwrap();
+        builder
+    }
+
+    #[test]
+    fn test_duplicate_policy() {
+        let mut builder = builder_with(DuplicatePolicy::Error);
+        assert_eq!(
+            builder.add_component(&1, "second").err(),
+            Some(DeviceInfoError::DuplicateComponent("1".to_string()))
+        );
+        assert_eq!(builder.components[&1], "first");
+
+        let mut builder = builder_with(DuplicatePolicy::KeepFirst);
+        builder.add_component(&1, "second").unwrap();
+        assert_eq!(builder.components[&1], "first");
+
+        let mut builder = builder_with(DuplicatePolicy::Overwrite);
+        builder.add_component(&1, "second").unwrap();
+        assert_eq!(builder.components[&1], "second");
+
+        let mut builder = builder_with(DuplicatePolicy::Append);
+        builder.add_component(&1, "second").unwrap();
+        assert_eq!(
+            builder.components[&1],
+            ComponentValue::list(["first", "second"])
+        );
+    }
+
+    #[test]
+    fn test_extend_components_reports_conflicts() {
+        let mut builder = builder_with(DuplicatePolicy::Error);
+        builder.extend_components(&HashMap::from([(1, "second".into()), (2, "".into())]));
+
+        assert_eq!(builder.components[&1], "first");
+        assert_eq!(builder.components[&2], "");
+        assert_eq!(
+            builder.failures[&1],
+            DeviceInfoError::DuplicateComponent("1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_conflicts_keep_value_and_errors() {
+        // A conflict leaves the first value usable
+        let mut builder = builder_with(DuplicatePolicy::Error);
+        builder.add_probed(&1);
+        assert!(!builder.is_unavailable(&1));
+        assert_eq!(builder.provenance[&1].confidence, Confidence::Medium);
+        assert_eq!(
+            builder.to_string(),
+            "1: first <conflict: Component '1' already exists>"
+        );
+
+        // and does not replace the error of a failed probe
+        let mut builder = BaseDeviceInfoBuilder::<u8>::new();
+        builder.add_probed(&1).add_probed(&1);
+        assert!(matches!(
+            builder.failures[&1],
+            DeviceInfoError::Unsupported(_)
+        ));
+
+        // A good value stored later clears the error
+        for policy in [DuplicatePolicy::Overwrite, DuplicatePolicy::Append] {
+            let mut builder = BaseDeviceInfoBuilder::<u8>::new();
+            builder.set_duplicate_policy(policy).add_probed(&1);
+            assert!(builder.is_unavailable(&1));
+            builder.merge_component(&1, "second");
+            assert!(!builder.is_unavailable(&1));
+            assert!(builder.failures.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_add_probed_records_provenance() {
+        let mut builder = BaseDeviceInfoBuilder::<u8>::new();
+        builder.add_probed(&1);
+
+        let provenance = &builder.provenance[&1];
+        assert_eq!(provenance.source, "1 (unknown source)");
+        assert_eq!(provenance.confidence, Confidence::Low);
+    }
 }
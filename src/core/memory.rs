@@ -0,0 +1,173 @@
+use std::{fmt, path::Path};
+
+use serde::Serialize;
+
+use crate::core::error::DeviceInfoError;
+use crate::core::smbios::SmbiosTables;
+
+/// An installed memory module (DIMM, SO-DIMM, ...)
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryModule {
+    pub slot: String,
+    pub size_mb: u64,
+    pub manufacturer: String,
+    pub part_number: String,
+    pub serial_number: String,
+}
+
+impl fmt::Display for MemoryModule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} MB, {}, {}, {}",
+            self.slot, self.size_mb, self.manufacturer, self.part_number, self.serial_number
+        )
+    }
+}
+
+/// Memory modules described by the SMBIOS memory device structures
+///
+/// Empty slots are left out.
+pub fn memory_modules_from_smbios(tables: &SmbiosTables) -> Vec<MemoryModule> {
+    tables
+        .memory_devices()
+        .iter()
+        .filter(|device| device.is_populated())
+        .map(|device| MemoryModule {
+            slot: device.device_locator.to_owned(),
+            size_mb: device.size_mb.unwrap_or_default(),
+            manufacturer: device.manufacturer.to_owned(),
+            part_number: device.part_number.to_owned(),
+            serial_number: device.serial_number.to_owned(),
+        })
+        .collect()
+}
+
+/// Total physical memory in bytes, as seen by the OS
+///
+/// `sysroot` only applies on Linux, where `/proc/meminfo` is read.
+#[allow(unused_variables)]
+pub fn total_memory(sysroot: &Path) -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        crate::plugins::linux::memory::read_mem_total(sysroot)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        crate::plugins::windows::memory::total_memory()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        crate::plugins::macos::memory::total_memory()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// List the installed memory modules of the current machine
+///
+/// `sysroot` only applies on Linux, where the SMBIOS tables are read from
+/// sysfs (root only). macOS does not expose a DIMM layout.
+#[allow(unused_variables)]
+pub fn list_memory_modules(sysroot: &Path) -> Result<Vec<MemoryModule>, DeviceInfoError> {
+    #[cfg(target_os = "linux")]
+    {
+        crate::plugins::linux::memory::list_memory_modules(sysroot)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        crate::plugins::windows::memory::list_memory_modules()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        Err(DeviceInfoError::Unsupported(
+            "Memory modules are not exposed on this platform".to_string(),
+        ))
+    }
+}
+
+/// Serial numbers of the memory modules, sorted and deduplicated
+///
+/// Sorting keeps the result stable when modules are moved between slots.
+pub fn memory_serial_numbers(modules: &[MemoryModule]) -> Vec<String> {
+    let mut serials: Vec<String> = modules
+        .iter()
+        .map(|module| module.serial_number.to_owned())
+        .filter(|serial| !serial.is_empty())
+        .collect();
+    serials.sort();
+    serials.dedup();
+    serials
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_modules_from_smbios() {
+        let tables = SmbiosTables::from_entry_point(
+            include_bytes!("fixtures/smbios3_entry_point.bin"),
+            include_bytes!("fixtures/smbios3_dmi.bin"),
+        )
+        .unwrap();
+        let modules = memory_modules_from_smbios(&tables);
+
+        assert_eq!(
+            modules,
+            vec![
+                MemoryModule {
+                    slot: "DIMM1".to_string(),
+                    size_mb: 8192,
+                    manufacturer: "Samsung".to_string(),
+                    part_number: "M378A2K43CB1-CTD".to_string(),
+                    serial_number: "1A2B3C4D".to_string(),
+                },
+                MemoryModule {
+                    slot: "DIMM2".to_string(),
+                    size_mb: 65536,
+                    manufacturer: "SK Hynix".to_string(),
+                    part_number: "HMA82GU6CJR8N-VK".to_string(),
+                    serial_number: "5E6F7A8B".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            modules[0].to_string(),
+            "DIMM1: 8192 MB, Samsung, M378A2K43CB1-CTD, 1A2B3C4D"
+        );
+        assert_eq!(
+            memory_serial_numbers(&modules),
+            vec!["1A2B3C4D".to_string(), "5E6F7A8B".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_memory_serial_numbers() {
+        let module = |slot: &str, serial: &str| MemoryModule {
+            slot: slot.to_string(),
+            size_mb: 4096,
+            manufacturer: "".to_string(),
+            part_number: "".to_string(),
+            serial_number: serial.to_string(),
+        };
+
+        assert_eq!(
+            memory_serial_numbers(&[
+                module("B", "0002"),
+                module("A", "0001"),
+                module("C", ""),
+                module("D", "0001"),
+            ]),
+            vec!["0001".to_string(), "0002".to_string()]
+        );
+    }
+}
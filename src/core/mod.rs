@@ -1,4 +1,18 @@
 pub mod builder;
+pub mod cache;
+pub mod concurrent;
 pub mod crypto;
+pub mod device_id;
+pub mod error;
 pub mod internal;
+pub mod memory;
+pub mod network;
+pub mod normalize;
+pub mod placeholder;
+pub mod profile;
+pub mod provenance;
+pub mod provider;
+pub mod smbios;
+pub mod sources;
 pub mod string_tools;
+pub mod value;
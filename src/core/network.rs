@@ -0,0 +1,115 @@
+use std::{fmt, path::Path};
+
+use serde::Serialize;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NetworkAdapterKind {
+    Physical,
+    Wireless,
+    Virtual,
+}
+
+impl fmt::Display for NetworkAdapterKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match *self {
+            NetworkAdapterKind::Physical => "physical",
+            NetworkAdapterKind::Wireless => "wireless",
+            NetworkAdapterKind::Virtual => "virtual",
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkAdapter {
+    pub name: String,
+    pub mac_address: String,
+    pub driver: Option<String>,
+    pub kind: NetworkAdapterKind,
+}
+
+impl NetworkAdapter {
+    /// Whether the adapter is backed by real hardware (wired or wireless)
+    pub fn is_physical(&self) -> bool {
+        self.kind != NetworkAdapterKind::Virtual
+    }
+}
+
+/// List the network adapters of the current machine
+///
+/// Adapters without a usable MAC address (loopback, point-to-point) are
+/// left out. `sysroot` only applies on Linux, where sysfs is read.
+#[allow(unused_variables)]
+pub fn list_network_adapters(sysroot: &Path) -> Vec<NetworkAdapter> {
+    #[cfg(target_os = "linux")]
+    {
+        crate::plugins::linux::network::list_network_adapters(sysroot)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        crate::plugins::windows::network::list_network_adapters()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        crate::plugins::macos::network::list_network_adapters()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+    {
+        vec![]
+    }
+}
+
+/// MAC addresses of the physical adapters, sorted and deduplicated
+///
+/// The order does not depend on the enumeration order of the OS, so the
+/// result is stable across reboots and driver reloads.
+pub fn physical_mac_addresses(adapters: &[NetworkAdapter]) -> Vec<String> {
+    let mut addresses: Vec<String> = adapters
+        .iter()
+        .filter(|adapter| adapter.is_physical())
+        .map(|adapter| adapter.mac_address.to_owned())
+        .collect();
+    addresses.sort();
+    addresses.dedup();
+    addresses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adapter(name: &str, mac_address: &str, kind: NetworkAdapterKind) -> NetworkAdapter {
+        NetworkAdapter {
+            name: name.to_string(),
+            mac_address: mac_address.to_string(),
+            driver: None,
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_physical_mac_addresses() {
+        let adapters = vec![
+            adapter("wlan0", "b4:2e:99:00:00:02", NetworkAdapterKind::Wireless),
+            adapter("docker0", "02:42:ac:11:00:01", NetworkAdapterKind::Virtual),
+            adapter("eth0", "b4:2e:99:00:00:01", NetworkAdapterKind::Physical),
+            adapter(
+                "eth0.100",
+                "b4:2e:99:00:00:01",
+                NetworkAdapterKind::Physical,
+            ),
+        ];
+
+        assert_eq!(
+            physical_mac_addresses(&adapters),
+            vec![
+                "b4:2e:99:00:00:01".to_string(),
+                "b4:2e:99:00:00:02".to_string()
+            ]
+        );
+    }
+}
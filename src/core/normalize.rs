@@ -0,0 +1,192 @@
+use crate::core::value::ComponentValue;
+
+/// How the raw value of a component is brought into canonical form before
+/// it is stored
+///
+/// The same fact is reported differently across probes and OS versions
+/// (`AA-BB-..` or `aa:bb:..`, padded serials, ...); normalizing avoids
+/// false mismatches when a machine code is checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalizer {
+    /// Keep the value as is, e.g. for application-defined values
+    Verbatim,
+    /// Trim surrounding whitespace
+    Trim,
+    /// Keep the first non-empty line, trim whitespace, NUL padding and
+    /// quotes, and upper case
+    Serial,
+    /// Lower case, colon separated
+    Mac,
+    /// Lower case, hyphenated
+    Uuid,
+    /// [`Normalizer::Uuid`], also ignoring the byte order of the first three
+    /// fields
+    ///
+    /// SMBIOS before 2.6 did not specify it, so firmwares and decoders
+    /// disagree; the smaller of both orders is kept.
+    SmbiosUuid,
+}
+
+impl Normalizer {
+    /// Whether the component identifies the hardware, and so may carry a
+    /// vendor placeholder instead
+    pub fn is_identifier(&self) -> bool {
+        matches!(
+            *self,
+            Normalizer::Serial | Normalizer::Mac | Normalizer::Uuid | Normalizer::SmbiosUuid
+        )
+    }
+
+    pub fn apply(&self, value: ComponentValue) -> ComponentValue {
+        match value {
+            ComponentValue::Single(value) => ComponentValue::Single(self.apply_str(&value)),
+            ComponentValue::List(values) => {
+                ComponentValue::list(values.iter().map(|value| self.apply_str(value)))
+            }
+        }
+    }
+
+    pub fn apply_str(&self, value: &str) -> String {
+        match *self {
+            Normalizer::Verbatim => value.to_string(),
+            Normalizer::Trim => value.trim().to_string(),
+            Normalizer::Serial => normalize_serial(value),
+            Normalizer::Mac => normalize_mac(value),
+            Normalizer::Uuid => normalize_uuid(value, false),
+            Normalizer::SmbiosUuid => normalize_uuid(value, true),
+        }
+    }
+}
+
+/// Commands listing several devices (one serial per line) are reduced to the
+/// first one
+fn normalize_serial(value: &str) -> String {
+    value
+        .lines()
+        .map(|line| line.trim_matches(|c: char| c.is_whitespace() || c == '\0' || c == '"'))
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+        .to_uppercase()
+}
+
+/// `aa:bb:cc:dd:ee:ff`, whatever the separator (`:`, `-`, `.` or none)
+///
+/// Values which are not 12 hex digits are only trimmed and lower cased.
+fn normalize_mac(value: &str) -> String {
+    let digits: String = value
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, ':' | '-' | '.'))
+        .collect::<String>()
+        .to_lowercase();
+    if digits.len() != 12 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return value.trim().to_lowercase();
+    }
+
+    digits
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| std::str::from_utf8(pair).unwrap())
+        .collect::<Vec<&str>>()
+        .join(":")
+}
+
+/// `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`, with or without braces and
+/// hyphens on input
+///
+/// Values which are not 32 hex digits are only trimmed and lower cased.
+fn normalize_uuid(value: &str, byte_order_insensitive: bool) -> String {
+    let digits: String = value
+        .trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .chars()
+        .filter(|c| *c != '-')
+        .collect::<String>()
+        .to_lowercase();
+    if digits.len() != 32 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return value.trim().to_lowercase();
+    }
+
+    let uuid = hyphenate_uuid(&digits);
+    match byte_order_insensitive {
+        true => uuid.min(hyphenate_uuid(&swap_uuid_fields(&digits))),
+        false => uuid,
+    }
+}
+
+fn hyphenate_uuid(digits: &str) -> String {
+    format!(
+        "{}-{}-{}-{}-{}",
+        &digits[0..8],
+        &digits[8..12],
+        &digits[12..16],
+        &digits[16..20],
+        &digits[20..32]
+    )
+}
+
+/// Reverse the byte order of the first three fields
+fn swap_uuid_fields(digits: &str) -> String {
+    let swap = |field: &str| {
+        field
+            .as_bytes()
+            .chunks(2)
+            .rev()
+            .map(|pair| std::str::from_utf8(pair).unwrap())
+            .collect::<String>()
+    };
+    format!(
+        "{}{}{}{}",
+        swap(&digits[0..8]),
+        swap(&digits[8..12]),
+        swap(&digits[12..16]),
+        &digits[16..32]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_mac() {
+        for raw in [
+            "AA-BB-CC-00-11-22",
+            "aa:bb:cc:00:11:22",
+            " aabb.cc00.1122\n",
+        ] {
+            assert_eq!(Normalizer::Mac.apply_str(raw), "aa:bb:cc:00:11:22");
+        }
+        assert_eq!(Normalizer::Mac.apply_str("00:11"), "00:11");
+    }
+
+    #[test]
+    fn test_normalize_uuid() {
+        assert_eq!(
+            Normalizer::Uuid.apply_str("{4C4C4544-0042-3510-8052-B4C04F335931}"),
+            "4c4c4544-0042-3510-8052-b4c04f335931"
+        );
+        assert_eq!(
+            Normalizer::SmbiosUuid.apply_str("4C4C4544-0042-3510-8052-B4C04F335931"),
+            Normalizer::SmbiosUuid.apply_str("44454c4c-4200-1035-8052-b4c04f335931")
+        );
+        assert_eq!(Normalizer::Uuid.apply_str("Not Settable "), "not settable");
+    }
+
+    #[test]
+    fn test_normalize_serial() {
+        assert_eq!(
+            Normalizer::Serial.apply_str("  \"c02xk1abjg5j\"\0\0\n"),
+            "C02XK1ABJG5J"
+        );
+        assert_eq!(
+            Normalizer::Serial.apply_str("\n0ba0179c20a14f27  \nS4EWNX0R123456A\n"),
+            "0BA0179C20A14F27"
+        );
+        assert_eq!(
+            Normalizer::Serial.apply(ComponentValue::list(["b ", " a", "A"])),
+            ComponentValue::list(["A", "B"])
+        );
+    }
+}
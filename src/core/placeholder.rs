@@ -0,0 +1,132 @@
+use crate::core::value::ComponentValue;
+
+/// What to do with a hardware identifier that is a known placeholder
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceholderPolicy {
+    /// Keep the value, without reporting it
+    Keep,
+    /// Keep the value and mark the component as unreliable
+    #[default]
+    Flag,
+    /// Drop the value and mark the component as unreliable
+    Drop,
+}
+
+/// Values firmwares and vendors ship instead of a real identifier, lower
+/// case and without surrounding whitespace
+const PLACEHOLDERS: [&str; 32] = [
+    "to be filled by o.e.m.",
+    "to be filled by oem",
+    "default string",
+    "default",
+    "o.e.m.",
+    "oem",
+    "none",
+    "null",
+    "n/a",
+    "na",
+    "not applicable",
+    "not available",
+    "not specified",
+    "not settable",
+    "not present",
+    "unknown",
+    "invalid",
+    "empty",
+    "undefined",
+    "system serial number",
+    "system product name",
+    "chassis serial number",
+    "base board serial number",
+    "serial number",
+    "serialnumber",
+    "sn",
+    "0123456789",
+    "123456789",
+    "1234567890",
+    "12345678",
+    "abcdefghij",
+    "xxxxxxxxxx",
+];
+
+/// Whether `value` is a known placeholder rather than a real identifier
+///
+/// Besides the curated list, values made of a single repeated character
+/// once separators are removed (all-zero and all-F UUIDs and MACs,
+/// `0000000000`, `..........`) are placeholders.
+pub fn is_placeholder(value: &str) -> bool {
+    let value = value.trim().to_lowercase();
+    if value.is_empty() {
+        return false;
+    }
+    if PLACEHOLDERS.contains(&value.as_str()) {
+        return true;
+    }
+
+    let mut chars = value.chars().filter(|c| !matches!(c, '-' | ':' | ' '));
+    match chars.next() {
+        Some(first) => chars.clone().count() > 0 && chars.all(|c| c == first),
+        None => true,
+    }
+}
+
+/// The placeholders among the values of a component
+pub fn placeholders(value: &ComponentValue) -> Vec<&str> {
+    value
+        .values()
+        .into_iter()
+        .filter(|value| is_placeholder(value))
+        .collect()
+}
+
+/// `value` without its placeholders; a single value is emptied
+pub fn drop_placeholders(value: ComponentValue) -> ComponentValue {
+    match value {
+        ComponentValue::Single(value) if is_placeholder(&value) => ComponentValue::default(),
+        ComponentValue::List(values) => {
+            ComponentValue::list(values.into_iter().filter(|value| !is_placeholder(value)))
+        }
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_placeholder() {
+        for value in [
+            "To Be Filled By O.E.M.",
+            "Default string",
+            "0123456789",
+            " None ",
+            "00000000-0000-0000-0000-000000000000",
+            "ffffffff-ffff-ffff-ffff-ffffffffffff",
+            "00:00:00:00:00:00",
+            "..........",
+        ] {
+            assert!(is_placeholder(value), "{}", value);
+        }
+        for value in [
+            "",
+            "0",
+            "PHNH912300ABC",
+            "4c4c4544-0042-3510-8052-b4c04f335931",
+        ] {
+            assert!(!is_placeholder(value), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_drop_placeholders() {
+        assert_eq!(
+            drop_placeholders("Default string".into()),
+            ComponentValue::default()
+        );
+        assert_eq!(
+            drop_placeholders(ComponentValue::list(["S3Z9NB0K", "None"])),
+            ComponentValue::list(["S3Z9NB0K"])
+        );
+    }
+}
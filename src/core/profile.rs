@@ -0,0 +1,225 @@
+use std::fmt;
+
+use crate::core::builder::MainBuilderComponents;
+use crate::plugins::linux::plugin::LinuxBuilderComponents;
+use crate::plugins::macos::plugin::MacOSBuilderComponents;
+use crate::plugins::windows::plugin::WindowsBuilderComponents;
+
+/// A named set of components, so every caller collects the same ones
+///
+/// A machine code only verifies against the profile it was issued with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    /// Stable identifiers of the device and its user, for machine codes and
+    /// fingerprints; never volatile components
+    Licensing,
+    /// Descriptive values without any identifier (OS, CPU, memory, ...)
+    Telemetry,
+    /// Everything that can be collected on the platform
+    Full,
+}
+
+impl Profile {
+    pub fn as_string(&self) -> String {
+        match *self {
+            Profile::Licensing => "licensing".to_string(),
+            Profile::Telemetry => "telemetry".to_string(),
+            Profile::Full => "full".to_string(),
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "licensing" => Some(Profile::Licensing),
+            "telemetry" => Some(Profile::Telemetry),
+            "full" => Some(Profile::Full),
+            _ => None,
+        }
+    }
+
+    /// The components of the profile on `platform`
+    pub fn components(&self, platform: &whoami::Platform) -> Vec<MainBuilderComponents> {
+        let windows = MainBuilderComponents::WindowsBuilderComponents;
+        let macos = MainBuilderComponents::MacOSBuilderComponents;
+        let linux = MainBuilderComponents::LinuxBuilderComponents;
+
+        match (*self, platform) {
+            // Windows identifies the user with the logon user name instead
+            (Profile::Licensing, whoami::Platform::Windows) => vec![
+                windows(WindowsBuilderComponents::LogonUserName),
+                windows(WindowsBuilderComponents::SystemDriveSerialNumber),
+                windows(WindowsBuilderComponents::MotherBoardSerialNumber),
+                windows(WindowsBuilderComponents::SystemUuid),
+            ],
+            (Profile::Licensing, whoami::Platform::MacOS) => vec![
+                MainBuilderComponents::UserName,
+                macos(MacOSBuilderComponents::PlatformSerialNumber),
+                macos(MacOSBuilderComponents::SystemDriveSerialNumber),
+            ],
+            (Profile::Licensing, whoami::Platform::Linux) => vec![
+                MainBuilderComponents::UserName,
+                linux(LinuxBuilderComponents::MachineId),
+                linux(LinuxBuilderComponents::ProductUuid),
+                linux(LinuxBuilderComponents::BoardSerialNumber),
+            ],
+            (Profile::Licensing, _) => vec![MainBuilderComponents::UserName],
+            (Profile::Telemetry, platform) => {
+                let mut components = vec![
+                    MainBuilderComponents::OSPlatform,
+                    MainBuilderComponents::OSDistro,
+                    MainBuilderComponents::CpuArch,
+                    MainBuilderComponents::MemoryTotal,
+                ];
+                if *platform == whoami::Platform::Linux {
+                    components.extend(
+                        [
+                            LinuxBuilderComponents::CpuVendor,
+                            LinuxBuilderComponents::CpuModelName,
+                            LinuxBuilderComponents::CpuSockets,
+                            LinuxBuilderComponents::CpuCores,
+                            LinuxBuilderComponents::CpuThreads,
+                            LinuxBuilderComponents::Virtualization,
+                            LinuxBuilderComponents::Uptime,
+                            LinuxBuilderComponents::OsId,
+                            LinuxBuilderComponents::OsVersionId,
+                            LinuxBuilderComponents::KernelRelease,
+                        ]
+                        .map(linux),
+                    );
+                }
+                components
+            }
+            (Profile::Full, platform) => {
+                let mut components = vec![
+                    MainBuilderComponents::UserName,
+                    MainBuilderComponents::OSPlatform,
+                    MainBuilderComponents::DeviceName,
+                    MainBuilderComponents::CpuArch,
+                    MainBuilderComponents::OSDistro,
+                    MainBuilderComponents::MACAddresses,
+                    MainBuilderComponents::MemoryTotal,
+                    MainBuilderComponents::MemoryModules,
+                    MainBuilderComponents::MemorySerialNumbers,
+                ];
+                match platform {
+                    whoami::Platform::Windows => components.extend(
+                        [
+                            WindowsBuilderComponents::LogonUserName,
+                            WindowsBuilderComponents::SystemDriveSerialNumber,
+                            WindowsBuilderComponents::MotherBoardSerialNumber,
+                            WindowsBuilderComponents::SystemUuid,
+                            WindowsBuilderComponents::MACAddress,
+                            WindowsBuilderComponents::ProcessorId,
+                            WindowsBuilderComponents::Guid,
+                            WindowsBuilderComponents::DiskSerialNumbers,
+                        ]
+                        .map(windows),
+                    ),
+                    whoami::Platform::MacOS => components.extend(
+                        [
+                            MacOSBuilderComponents::PlatformSerialNumber,
+                            MacOSBuilderComponents::SystemDriveSerialNumber,
+                        ]
+                        .map(macos),
+                    ),
+                    whoami::Platform::Linux => components.extend(
+                        [
+                            LinuxBuilderComponents::MachineId,
+                            LinuxBuilderComponents::ProductUuid,
+                            LinuxBuilderComponents::BoardSerialNumber,
+                            LinuxBuilderComponents::ProductSerialNumber,
+                            LinuxBuilderComponents::ChassisSerialNumber,
+                            LinuxBuilderComponents::SystemSku,
+                            LinuxBuilderComponents::OemStrings,
+                            LinuxBuilderComponents::ProcessorSerialNumbers,
+                            LinuxBuilderComponents::SystemDriveSerialNumber,
+                            LinuxBuilderComponents::CpuVendor,
+                            LinuxBuilderComponents::CpuModelName,
+                            LinuxBuilderComponents::CpuFamily,
+                            LinuxBuilderComponents::CpuModel,
+                            LinuxBuilderComponents::CpuStepping,
+                            LinuxBuilderComponents::CpuMicrocode,
+                            LinuxBuilderComponents::CpuSockets,
+                            LinuxBuilderComponents::CpuCores,
+                            LinuxBuilderComponents::CpuThreads,
+                            LinuxBuilderComponents::CpuFlagsHash,
+                            LinuxBuilderComponents::Virtualization,
+                            LinuxBuilderComponents::BootId,
+                            LinuxBuilderComponents::BootTime,
+                            LinuxBuilderComponents::Uptime,
+                            LinuxBuilderComponents::OsId,
+                            LinuxBuilderComponents::OsIdLike,
+                            LinuxBuilderComponents::OsVersionId,
+                            LinuxBuilderComponents::OsVersionCodename,
+                            LinuxBuilderComponents::OsBuildId,
+                            LinuxBuilderComponents::OsVariantId,
+                            LinuxBuilderComponents::KernelRelease,
+                            LinuxBuilderComponents::KernelVersion,
+                        ]
+                        .map(linux),
+                    ),
+                    _ => {}
+                }
+                components
+            }
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_string().as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLATFORMS: [whoami::Platform; 3] = [
+        whoami::Platform::Windows,
+        whoami::Platform::MacOS,
+        whoami::Platform::Linux,
+    ];
+
+    #[test]
+    fn test_licensing_is_stable() {
+        for platform in PLATFORMS {
+            let components = Profile::Licensing.components(&platform);
+            assert!(!components.is_empty());
+            assert!(components.iter().all(|name| !name.is_volatile()));
+        }
+        assert!(Profile::Licensing
+            .components(&whoami::Platform::Windows)
+            .iter()
+            .all(|name| *name != MainBuilderComponents::UserName));
+    }
+
+    #[test]
+    fn test_telemetry_has_no_identifier() {
+        for platform in PLATFORMS {
+            assert!(Profile::Telemetry
+                .components(&platform)
+                .iter()
+                .all(|name| !name.normalizer().is_identifier()
+                    && *name != MainBuilderComponents::UserName));
+        }
+    }
+
+    #[test]
+    fn test_full_contains_other_profiles() {
+        for platform in PLATFORMS {
+            let full = Profile::Full.components(&platform);
+            for profile in [Profile::Licensing, Profile::Telemetry] {
+                assert!(profile
+                    .components(&platform)
+                    .iter()
+                    .all(|name| full.contains(name)));
+            }
+        }
+        for profile in [Profile::Licensing, Profile::Telemetry, Profile::Full] {
+            assert_eq!(Profile::from_str(&profile.to_string()), Some(profile));
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// How far the value of a component can be trusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    /// The probe failed, or the value is a vendor placeholder
+    Low,
+    /// The value is derived with a heuristic (physical network adapters,
+    /// the disk backing `/`, ...) or chosen by the application
+    Medium,
+    /// The value is read as is from the firmware or the OS
+    High,
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match *self {
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        })
+    }
+}
+
+/// Where and when the value of a component was collected
+///
+/// Kept for every probed component, to find out why a machine code stopped
+/// matching.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Provenance {
+    /// The sysfs path, WMI query, registry key, command line, ... read
+    pub source: String,
+    /// Seconds since the Unix epoch
+    pub collected_at: u64,
+    #[serde(
+        rename = "durationMs",
+        serialize_with = "serialize_millis",
+        deserialize_with = "deserialize_millis"
+    )]
+    pub duration: Duration,
+    pub confidence: Confidence,
+}
+
+impl Provenance {
+    /// Collected now, instantly, e.g. a value set by the application
+    pub fn new<S: Into<String>>(source: S, confidence: Confidence) -> Self {
+        Self {
+            source: source.into(),
+            collected_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|now| now.as_secs())
+                .unwrap_or_default(),
+            duration: Duration::ZERO,
+            confidence,
+        }
+    }
+
+    /// Run `probe` and time it
+    pub fn trace<S, T, F>(source: S, confidence: Confidence, probe: F) -> (T, Self)
+    where
+        S: Into<String>,
+        F: FnOnce() -> T,
+    {
+        let mut provenance = Self::new(source, confidence);
+        let started = Instant::now();
+        let value = probe();
+        provenance.duration = started.elapsed();
+        (value, provenance)
+    }
+}
+
+fn serialize_millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    (duration.as_millis() as u64).serialize(serializer)
+}
+
+fn deserialize_millis<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    u64::deserialize(deserializer).map(Duration::from_millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace() {
+        let (value, provenance) = Provenance::trace("/etc/machine-id", Confidence::High, || {
+            std::thread::sleep(Duration::from_millis(20));
+            42
+        });
+        assert_eq!(value, 42);
+        assert!(provenance.duration >= Duration::from_millis(20));
+        assert!(provenance.collected_at > 0);
+
+        let json = serde_json::to_value(&provenance).unwrap();
+        assert_eq!(json["source"], "/etc/machine-id");
+        assert_eq!(json["confidence"], "high");
+        assert!(json["durationMs"].as_u64().unwrap() >= 20);
+        assert_eq!(
+            serde_json::from_value::<Provenance>(json).unwrap().duration,
+            Duration::from_millis(provenance.duration.as_millis() as u64)
+        );
+    }
+}
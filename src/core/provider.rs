@@ -0,0 +1,33 @@
+use crate::core::error::DeviceInfoError;
+use crate::core::value::ComponentValue;
+
+/// Namespaces used by the built-in components
+pub const RESERVED_NAMESPACES: [&str; 10] = [
+    "Windows",
+    "MacOS",
+    "Linux",
+    "Custom",
+    "File",
+    "FileSha256",
+    "Env",
+    "EnvSha256",
+    "Command",
+    "CommandSha256",
+];
+
+/// Source of components living outside of this crate
+///
+/// Providers are registered with
+/// [`MainDeviceInfoBuilder::register_provider`](crate::core::builder::MainDeviceInfoBuilder::register_provider)
+/// and their components are keyed `<namespace>::<component>`, like the
+/// platform ones.
+pub trait ComponentProvider: Send + Sync {
+    /// Prefix of the component keys, e.g. `Acme` for `Acme::licenseSeat`
+    fn namespace(&self) -> &str;
+
+    /// Names of the components this provider can collect
+    fn components(&self) -> Vec<String>;
+
+    /// Collect one of [`Self::components`]
+    fn collect(&self, component: &str) -> Result<ComponentValue, DeviceInfoError>;
+}
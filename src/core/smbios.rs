@@ -0,0 +1,577 @@
+//! SMBIOS (DMI) table decoder
+//!
+//! Decodes the structures this crate draws on: BIOS (type 0), system (1),
+//! baseboard (2), chassis (3), processor (4), OEM strings (11) and memory
+//! device (17). Other structures are kept as [`SmbiosStructure::Other`].
+//!
+//! Tables come either from the Linux sysfs export
+//! (`/sys/firmware/dmi/tables/{smbios_entry_point,DMI}`), from an entry
+//! point plus table blob, or from the raw SMBIOS data Windows returns for
+//! the `RSMB` firmware table provider.
+
+use std::{fs, path::Path};
+
+use crate::core::error::DeviceInfoError;
+
+/// Structure type terminating the table
+const END_OF_TABLE: u8 = 127;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BiosInformation {
+    pub vendor: String,
+    pub version: String,
+    pub release_date: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemInformation {
+    pub manufacturer: String,
+    pub product_name: String,
+    pub version: String,
+    pub serial_number: String,
+    pub uuid: Option<String>,
+    pub sku_number: String,
+    pub family: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseboardInformation {
+    pub manufacturer: String,
+    pub product: String,
+    pub version: String,
+    pub serial_number: String,
+    pub asset_tag: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChassisInformation {
+    pub manufacturer: String,
+    pub chassis_type: u8,
+    pub version: String,
+    pub serial_number: String,
+    pub asset_tag: String,
+    pub sku_number: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessorInformation {
+    pub socket_designation: String,
+    pub manufacturer: String,
+    /// Raw CPUID signature and feature flags
+    pub processor_id: u64,
+    pub version: String,
+    pub serial_number: String,
+    pub asset_tag: String,
+    pub part_number: String,
+    pub core_count: Option<u8>,
+    pub thread_count: Option<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryDevice {
+    pub device_locator: String,
+    pub bank_locator: String,
+    /// Size in MiB; `None` when unknown, `Some(0)` for an empty slot
+    pub size_mb: Option<u64>,
+    pub form_factor: u8,
+    pub memory_type: u8,
+    pub speed_mts: Option<u16>,
+    pub manufacturer: String,
+    pub serial_number: String,
+    pub asset_tag: String,
+    pub part_number: String,
+}
+
+impl MemoryDevice {
+    /// Whether a module is installed in the slot
+    pub fn is_populated(&self) -> bool {
+        self.size_mb != Some(0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SmbiosStructure {
+    Bios(BiosInformation),
+    System(SystemInformation),
+    Baseboard(BaseboardInformation),
+    Chassis(ChassisInformation),
+    Processor(ProcessorInformation),
+    OemStrings(Vec<String>),
+    MemoryDevice(MemoryDevice),
+    Other { kind: u8, handle: u16 },
+}
+
+/// One structure before decoding: formatted area and string set
+struct RawStructure<'a> {
+    kind: u8,
+    handle: u16,
+    formatted: &'a [u8],
+    strings: Vec<String>,
+}
+
+impl<'a> RawStructure<'a> {
+    fn byte(&self, offset: usize) -> Option<u8> {
+        self.formatted.get(offset).copied()
+    }
+
+    fn word(&self, offset: usize) -> Option<u16> {
+        let bytes = self.formatted.get(offset..offset + 2)?;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn dword(&self, offset: usize) -> Option<u32> {
+        let bytes = self.formatted.get(offset..offset + 4)?;
+        Some(u32::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    fn qword(&self, offset: usize) -> Option<u64> {
+        let bytes = self.formatted.get(offset..offset + 8)?;
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    /// String referenced by the index byte at `offset`, empty when unset
+    fn string(&self, offset: usize) -> String {
+        self.byte(offset)
+            .filter(|index| *index > 0)
+            .and_then(|index| self.strings.get(index as usize - 1))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Format the 16 UUID bytes of the system structure
+///
+/// Since SMBIOS 2.6 the first three fields are little-endian; older
+/// versions store the whole UUID in network byte order. All-zero and
+/// all-`0xFF` UUIDs mean "not present" and "not settable".
+fn format_uuid(bytes: &[u8], version: (u8, u8)) -> Option<String> {
+    if bytes.len() != 16 || bytes.iter().all(|b| *b == 0) || bytes.iter().all(|b| *b == 0xFF) {
+        return None;
+    }
+
+    let mut bytes = bytes.to_vec();
+    if version >= (2, 6) {
+        bytes[0..4].reverse();
+        bytes[4..6].reverse();
+        bytes[6..8].reverse();
+    }
+    let hex = hex::encode(bytes);
+    Some(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    ))
+}
+
+fn decode(raw: &RawStructure, version: (u8, u8)) -> SmbiosStructure {
+    match raw.kind {
+        0 => SmbiosStructure::Bios(BiosInformation {
+            vendor: raw.string(0x04),
+            version: raw.string(0x05),
+            release_date: raw.string(0x08),
+        }),
+        1 => SmbiosStructure::System(SystemInformation {
+            manufacturer: raw.string(0x04),
+            product_name: raw.string(0x05),
+            version: raw.string(0x06),
+            serial_number: raw.string(0x07),
+            uuid: raw
+                .formatted
+                .get(0x08..0x18)
+                .and_then(|bytes| format_uuid(bytes, version)),
+            sku_number: raw.string(0x19),
+            family: raw.string(0x1A),
+        }),
+        2 => SmbiosStructure::Baseboard(BaseboardInformation {
+            manufacturer: raw.string(0x04),
+            product: raw.string(0x05),
+            version: raw.string(0x06),
+            serial_number: raw.string(0x07),
+            asset_tag: raw.string(0x08),
+        }),
+        3 => {
+            // The SKU follows the variable-length contained elements
+            let contained =
+                raw.byte(0x13).unwrap_or(0) as usize * raw.byte(0x14).unwrap_or(0) as usize;
+            SmbiosStructure::Chassis(ChassisInformation {
+                manufacturer: raw.string(0x04),
+                chassis_type: raw.byte(0x05).unwrap_or(0) & 0x7F,
+                version: raw.string(0x06),
+                serial_number: raw.string(0x07),
+                asset_tag: raw.string(0x08),
+                sku_number: raw.string(0x15 + contained),
+            })
+        }
+        4 => SmbiosStructure::Processor(ProcessorInformation {
+            socket_designation: raw.string(0x04),
+            manufacturer: raw.string(0x07),
+            processor_id: raw.qword(0x08).unwrap_or(0),
+            version: raw.string(0x10),
+            serial_number: raw.string(0x20),
+            asset_tag: raw.string(0x21),
+            part_number: raw.string(0x22),
+            core_count: raw.byte(0x23),
+            thread_count: raw.byte(0x25),
+        }),
+        11 => SmbiosStructure::OemStrings(
+            (0..raw.byte(0x04).unwrap_or(0) as usize)
+                .filter_map(|index| raw.strings.get(index).cloned())
+                .collect(),
+        ),
+        17 => SmbiosStructure::MemoryDevice(MemoryDevice {
+            device_locator: raw.string(0x10),
+            bank_locator: raw.string(0x11),
+            size_mb: match raw.word(0x0C) {
+                None | Some(0xFFFF) => None,
+                Some(0x7FFF) => raw.dword(0x1C).map(|size| (size & 0x7FFF_FFFF) as u64),
+                Some(size) if size & 0x8000 != 0 => Some((size & 0x7FFF) as u64 / 1024),
+                Some(size) => Some(size as u64),
+            },
+            form_factor: raw.byte(0x0E).unwrap_or(0),
+            memory_type: raw.byte(0x12).unwrap_or(0),
+            speed_mts: raw.word(0x15).filter(|speed| *speed != 0),
+            manufacturer: raw.string(0x17),
+            serial_number: raw.string(0x18),
+            asset_tag: raw.string(0x19),
+            part_number: raw.string(0x1A),
+        }),
+        kind => SmbiosStructure::Other {
+            kind,
+            handle: raw.handle,
+        },
+    }
+}
+
+/// Split a table into raw structures, stopping at the end-of-table marker
+/// or at the first truncated structure
+fn split_structures(table: &[u8]) -> Vec<RawStructure<'_>> {
+    let mut structures = vec![];
+    let mut offset = 0;
+
+    while offset + 4 <= table.len() {
+        let kind = table[offset];
+        let length = table[offset + 1] as usize;
+        let handle = u16::from_le_bytes([table[offset + 2], table[offset + 3]]);
+        if length < 4 || offset + length > table.len() {
+            break;
+        }
+
+        // The string set ends with a double NUL
+        let strings_start = offset + length;
+        let Some(strings_length) = table[strings_start..]
+            .windows(2)
+            .position(|pair| pair == [0, 0])
+        else {
+            break;
+        };
+        let strings = table[strings_start..strings_start + strings_length]
+            .split(|b| *b == 0)
+            .filter(|s| !s.is_empty())
+            .map(|s| String::from_utf8_lossy(s).trim().to_string())
+            .collect();
+
+        structures.push(RawStructure {
+            kind,
+            handle,
+            formatted: &table[offset..offset + length],
+            strings,
+        });
+        if kind == END_OF_TABLE {
+            break;
+        }
+        offset = strings_start + strings_length + 2;
+    }
+
+    structures
+}
+
+/// Decoded SMBIOS tables
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmbiosTables {
+    /// SMBIOS (major, minor) version
+    pub version: (u8, u8),
+    pub structures: Vec<SmbiosStructure>,
+}
+
+impl SmbiosTables {
+    /// Decode a structure table of the given SMBIOS version
+    pub fn parse(table: &[u8], version: (u8, u8)) -> Self {
+        Self {
+            version,
+            structures: split_structures(table)
+                .iter()
+                .filter(|raw| raw.kind != END_OF_TABLE)
+                .map(|raw| decode(raw, version))
+                .collect(),
+        }
+    }
+
+    /// Decode a table with the version taken from its entry point
+    /// (`_SM3_`, `_SM_` or legacy `_DMI_`)
+    pub fn from_entry_point(entry_point: &[u8], table: &[u8]) -> Option<Self> {
+        let version = parse_entry_point_version(entry_point)?;
+        Some(Self::parse(table, version))
+    }
+
+    /// Decode the raw SMBIOS data returned by Windows for the `RSMB`
+    /// firmware table: an 8-byte header followed by the table
+    pub fn from_raw_smbios_data(data: &[u8]) -> Option<Self> {
+        let header = data.get(0..8)?;
+        let length = u32::from_le_bytes(header[4..8].try_into().ok()?) as usize;
+        let table = data.get(8..8 + length)?;
+        Some(Self::parse(table, (header[1], header[2])))
+    }
+
+    /// Read `<root>/sys/firmware/dmi/tables`, which only root can read
+    pub fn from_sysfs(root: &Path) -> Result<Self, DeviceInfoError> {
+        let read = |name: &str| {
+            let path = root.join("sys/firmware/dmi/tables").join(name);
+            fs::read(&path).map_err(|e| DeviceInfoError::from_io(&path.to_string_lossy(), e))
+        };
+        let entry_point = read("smbios_entry_point")?;
+        let table = read("DMI")?;
+        Self::from_entry_point(&entry_point, &table)
+            .ok_or_else(|| DeviceInfoError::Parse("Invalid SMBIOS entry point".to_string()))
+    }
+
+    pub fn bios(&self) -> Option<&BiosInformation> {
+        self.structures
+            .iter()
+            .find_map(|structure| match structure {
+                SmbiosStructure::Bios(bios) => Some(bios),
+                _ => None,
+            })
+    }
+
+    pub fn system(&self) -> Option<&SystemInformation> {
+        self.structures
+            .iter()
+            .find_map(|structure| match structure {
+                SmbiosStructure::System(system) => Some(system),
+                _ => None,
+            })
+    }
+
+    pub fn baseboard(&self) -> Option<&BaseboardInformation> {
+        self.structures
+            .iter()
+            .find_map(|structure| match structure {
+                SmbiosStructure::Baseboard(baseboard) => Some(baseboard),
+                _ => None,
+            })
+    }
+
+    pub fn chassis(&self) -> Option<&ChassisInformation> {
+        self.structures
+            .iter()
+            .find_map(|structure| match structure {
+                SmbiosStructure::Chassis(chassis) => Some(chassis),
+                _ => None,
+            })
+    }
+
+    pub fn processors(&self) -> Vec<&ProcessorInformation> {
+        self.structures
+            .iter()
+            .filter_map(|structure| match structure {
+                SmbiosStructure::Processor(processor) => Some(processor),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// OEM strings of every type 11 structure, in table order
+    pub fn oem_strings(&self) -> Vec<&str> {
+        self.structures
+            .iter()
+            .filter_map(|structure| match structure {
+                SmbiosStructure::OemStrings(strings) => Some(strings),
+                _ => None,
+            })
+            .flatten()
+            .map(|s| s.as_str())
+            .collect()
+    }
+
+    pub fn memory_devices(&self) -> Vec<&MemoryDevice> {
+        self.structures
+            .iter()
+            .filter_map(|structure| match structure {
+                SmbiosStructure::MemoryDevice(device) => Some(device),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Length of the 64-bit (`_SM3_`) and 32-bit (`_SM_`) entry points
+const SMBIOS3_ENTRY_POINT_LENGTH: usize = 0x18;
+const SMBIOS2_ENTRY_POINT_LENGTH: usize = 0x1F;
+
+/// SMBIOS version announced by an entry point, after checking its checksum
+pub fn parse_entry_point_version(entry_point: &[u8]) -> Option<(u8, u8)> {
+    // The announced length covers at least the fields of the entry point
+    let checksum_ok = |length: usize, minimum: usize| {
+        length >= minimum
+            && entry_point
+                .get(0..length)
+                .is_some_and(|bytes| bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)) == 0)
+    };
+
+    if entry_point.starts_with(b"_SM3_") {
+        let length = *entry_point.get(6)? as usize;
+        checksum_ok(length, SMBIOS3_ENTRY_POINT_LENGTH).then(|| (entry_point[7], entry_point[8]))
+    } else if entry_point.starts_with(b"_SM_") {
+        let length = *entry_point.get(5)? as usize;
+        checksum_ok(length, SMBIOS2_ENTRY_POINT_LENGTH).then(|| (entry_point[6], entry_point[7]))
+    } else if entry_point.starts_with(b"_DMI_") {
+        let revision = *entry_point.get(14)?;
+        checksum_ok(15, 15).then_some((revision >> 4, revision & 0x0F))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMBIOS3_ENTRY_POINT: &[u8] = include_bytes!("fixtures/smbios3_entry_point.bin");
+    const SMBIOS3_DMI: &[u8] = include_bytes!("fixtures/smbios3_dmi.bin");
+    const SMBIOS2_ENTRY_POINT: &[u8] = include_bytes!("fixtures/smbios2_entry_point.bin");
+    const SMBIOS2_DMI: &[u8] = include_bytes!("fixtures/smbios2_dmi.bin");
+
+    #[test]
+    fn test_parse_entry_point_version() {
+        assert_eq!(parse_entry_point_version(SMBIOS3_ENTRY_POINT), Some((3, 2)));
+        assert_eq!(parse_entry_point_version(SMBIOS2_ENTRY_POINT), Some((2, 4)));
+
+        let mut corrupted = SMBIOS3_ENTRY_POINT.to_vec();
+        corrupted[10] ^= 0xFF;
+        assert_eq!(parse_entry_point_version(&corrupted), None);
+        assert_eq!(parse_entry_point_version(b"garbage"), None);
+
+        // Truncated entry points announcing their own short length, with a
+        // matching checksum
+        let truncated = |entry_point: &[u8], length: usize, checksum_offset: usize| {
+            let mut truncated = entry_point[..length].to_vec();
+            truncated[checksum_offset + 1] = length as u8;
+            let sum = truncated.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+            truncated[checksum_offset] = truncated[checksum_offset].wrapping_sub(sum);
+            truncated
+        };
+        for length in 7..=8 {
+            let entry_point = truncated(SMBIOS3_ENTRY_POINT, length, 5);
+            assert_eq!(parse_entry_point_version(&entry_point), None);
+        }
+        for length in 6..=7 {
+            let entry_point = truncated(SMBIOS2_ENTRY_POINT, length, 4);
+            assert_eq!(parse_entry_point_version(&entry_point), None);
+        }
+    }
+
+    #[test]
+    fn test_decode_smbios3() {
+        let tables = SmbiosTables::from_entry_point(SMBIOS3_ENTRY_POINT, SMBIOS3_DMI).unwrap();
+
+        assert_eq!(tables.bios().unwrap().version, "F.20");
+
+        let system = tables.system().unwrap();
+        assert_eq!(system.manufacturer, "Dell Inc.");
+        assert_eq!(system.serial_number, "7Q5ZKZ2");
+        assert_eq!(
+            system.uuid.as_deref(),
+            Some("4c4c4544-0051-3510-8057-b7c04f4b5a32")
+        );
+        assert_eq!(system.sku_number, "SKU=0859;ModelName=OptiPlex 7060");
+        assert_eq!(system.family, "OptiPlex");
+
+        let baseboard = tables.baseboard().unwrap();
+        assert_eq!(baseboard.product, "0C96W1");
+        assert_eq!(baseboard.serial_number, "/7Q5ZKZ2/CNWS20089O0123/");
+
+        let chassis = tables.chassis().unwrap();
+        assert_eq!(chassis.chassis_type, 3);
+        assert_eq!(chassis.sku_number, "Chassis SKU");
+
+        let processors = tables.processors();
+        assert_eq!(processors.len(), 2);
+        assert_eq!(processors[1].socket_designation, "CPU1");
+        assert_eq!(processors[1].serial_number, "PSN-0002");
+        assert_eq!(processors[0].processor_id, 0xBFEBFBFF000906EA);
+        assert_eq!(processors[0].thread_count, Some(12));
+
+        assert_eq!(
+            tables.oem_strings(),
+            vec!["Dell System", "1[0859]", "APPLIANCE:acme-edge-01"]
+        );
+    }
+
+    #[test]
+    fn test_decode_memory_devices() {
+        let tables = SmbiosTables::from_entry_point(SMBIOS3_ENTRY_POINT, SMBIOS3_DMI).unwrap();
+        let devices = tables.memory_devices();
+
+        assert_eq!(devices.len(), 3);
+        assert_eq!(devices[0].device_locator, "DIMM1");
+        assert_eq!(devices[0].size_mb, Some(8192));
+        assert_eq!(devices[0].part_number, "M378A2K43CB1-CTD");
+        assert_eq!(devices[0].speed_mts, Some(2666));
+        assert_eq!(devices[1].size_mb, Some(65536));
+        assert_eq!(devices[1].serial_number, "5E6F7A8B");
+        assert!(!devices[2].is_populated());
+    }
+
+    #[test]
+    fn test_decode_smbios2_uuid_byte_order() {
+        let tables = SmbiosTables::from_entry_point(SMBIOS2_ENTRY_POINT, SMBIOS2_DMI).unwrap();
+
+        assert_eq!(tables.version, (2, 4));
+        assert_eq!(
+            tables.system().unwrap().uuid.as_deref(),
+            Some("4c4c4544-0051-3510-8057-b7c04f4b5a32")
+        );
+    }
+
+    #[test]
+    fn test_raw_smbios_data_and_truncation() {
+        let mut data = vec![0, 3, 2, 0];
+        data.extend_from_slice(&(SMBIOS3_DMI.len() as u32).to_le_bytes());
+        data.extend_from_slice(SMBIOS3_DMI);
+        let tables = SmbiosTables::from_raw_smbios_data(&data).unwrap();
+        assert_eq!(tables.version, (3, 2));
+        assert_eq!(tables.structures.len(), 10);
+
+        // A cut-off table keeps the structures decoded so far
+        let truncated = SmbiosTables::parse(&SMBIOS3_DMI[..120], (3, 2));
+        assert!(truncated.bios().is_some());
+        assert!(truncated.memory_devices().is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_from_sysfs() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        assert!(matches!(
+            SmbiosTables::from_sysfs(root.path()),
+            Err(DeviceInfoError::NotFound(_))
+        ));
+
+        root.file(
+            "sys/firmware/dmi/tables/smbios_entry_point",
+            SMBIOS3_ENTRY_POINT,
+        )
+        .file("sys/firmware/dmi/tables/DMI", SMBIOS3_DMI);
+        assert_eq!(
+            SmbiosTables::from_sysfs(root.path())
+                .unwrap()
+                .system()
+                .unwrap()
+                .product_name,
+            "OptiPlex 7060"
+        );
+    }
+}
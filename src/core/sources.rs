@@ -0,0 +1,437 @@
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use sha2::{Digest, Sha256};
+
+use crate::core::error::DeviceInfoError;
+use crate::core::string_tools::strip_trailing_newline;
+
+/// Components read from a source chosen by the application: a file, an
+/// environment variable or the output of a command
+///
+/// With `hashed`, the SHA-256 of the value is stored instead of the value
+/// itself, e.g. for a config file or a secret handed down by orchestration.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, PartialOrd, Ord)]
+pub enum SourceComponents {
+    FileContents {
+        path: String,
+        hashed: bool,
+    },
+    EnvVar {
+        name: String,
+        hashed: bool,
+    },
+    /// The program followed by its arguments
+    CommandOutput {
+        command: Vec<String>,
+        hashed: bool,
+    },
+}
+
+impl SourceComponents {
+    /// `File::<path>`, `Env::<name>` or `Command::<JSON array>`, with a
+    /// `Sha256` suffix on the kind when hashed
+    pub fn as_string(&self) -> String {
+        let (kind, target, hashed) = match *self {
+            SourceComponents::FileContents { ref path, hashed } => {
+                ("File", path.to_owned(), hashed)
+            }
+            SourceComponents::EnvVar { ref name, hashed } => ("Env", name.to_owned(), hashed),
+            SourceComponents::CommandOutput {
+                ref command,
+                hashed,
+            } => (
+                "Command",
+                serde_json::to_string(command).unwrap_or_default(),
+                hashed,
+            ),
+        };
+        match hashed {
+            true => format!("{}Sha256::{}", kind, target),
+            false => format!("{}::{}", kind, target),
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        let (kind, target) = s.split_once("::")?;
+        let (kind, hashed) = match kind.strip_suffix("Sha256") {
+            Some(kind) => (kind, true),
+            None => (kind, false),
+        };
+        if target.is_empty() {
+            return None;
+        }
+
+        match kind {
+            "File" => Some(SourceComponents::FileContents {
+                path: target.to_string(),
+                hashed,
+            }),
+            "Env" => Some(SourceComponents::EnvVar {
+                name: target.to_string(),
+                hashed,
+            }),
+            "Command" => serde_json::from_str::<Vec<String>>(target)
+                .ok()
+                .filter(|command| !command.is_empty())
+                .map(|command| SourceComponents::CommandOutput { command, hashed }),
+            _ => None,
+        }
+    }
+
+    /// Whether the source can be read below a sysroot other than `/`
+    ///
+    /// Only files can; environment variables and commands belong to the
+    /// running system.
+    pub fn supports_sysroot(&self) -> bool {
+        matches!(self, SourceComponents::FileContents { .. })
+    }
+
+    /// Read the value, with the trailing newline stripped
+    ///
+    /// Files are read below `sysroot`, or from the current directory when
+    /// relative and `sysroot` is `/`. Reading a file or running a command
+    /// gives up after `timeout`; a command still running then is killed.
+    pub fn read(&self, sysroot: &Path, timeout: Duration) -> Result<String, DeviceInfoError> {
+        let (value, hashed) = match *self {
+            SourceComponents::FileContents { ref path, hashed } => {
+                (read_file(&file_path(path, sysroot)?, timeout)?, hashed)
+            }
+            SourceComponents::EnvVar { ref name, hashed } => (read_env_var(name)?, hashed),
+            SourceComponents::CommandOutput {
+                ref command,
+                hashed,
+            } => (run_command(command, timeout)?, hashed),
+        };
+        self.finish(value, hashed)
+    }
+
+    /// [`Self::read`] without blocking the tokio runtime: commands are
+    /// spawned with `tokio::process`, and killed when they time out
+    #[cfg(feature = "async")]
+    pub async fn read_async(
+        &self,
+        sysroot: &Path,
+        timeout: Duration,
+    ) -> Result<String, DeviceInfoError> {
+        let (value, hashed) = match *self {
+            SourceComponents::FileContents { ref path, hashed } => (
+                read_file_async(&file_path(path, sysroot)?, timeout).await?,
+                hashed,
+            ),
+            SourceComponents::EnvVar { ref name, hashed } => (read_env_var(name)?, hashed),
+            SourceComponents::CommandOutput {
+                ref command,
+                hashed,
+            } => (run_command_async(command, timeout).await?, hashed),
+        };
+        self.finish(value, hashed)
+    }
+
+    fn finish(&self, value: Vec<u8>, hashed: bool) -> Result<String, DeviceInfoError> {
+        match hashed {
+            true => Ok(hex::encode(Sha256::digest(strip_trailing_newline_bytes(
+                &value,
+            )))),
+            false => String::from_utf8(value)
+                .map(|value| strip_trailing_newline(&value).to_string())
+                .map_err(|e| DeviceInfoError::Parse(format!("{}: {}", self, e))),
+        }
+    }
+}
+
+impl fmt::Display for SourceComponents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_string().as_str())
+    }
+}
+
+fn strip_trailing_newline_bytes(input: &[u8]) -> &[u8] {
+    input
+        .strip_suffix(b"\r\n")
+        .or(input.strip_suffix(b"\n"))
+        .unwrap_or(input)
+}
+
+/// Where the file at `path` is read
+///
+/// Absolute paths are taken below `sysroot`. Relative paths are resolved
+/// against the current directory, so they are refused below a custom sysroot,
+/// where they would name a file of the running system.
+fn file_path(path: &str, sysroot: &Path) -> Result<PathBuf, DeviceInfoError> {
+    match (Path::new(path).is_absolute(), sysroot == Path::new("/")) {
+        (true, _) => Ok(sysroot.join(path.trim_start_matches('/'))),
+        (false, true) => Ok(PathBuf::from(path)),
+        (false, false) => Err(DeviceInfoError::Unsupported(format!(
+            "Relative path {} cannot be read below {}",
+            path,
+            sysroot.display()
+        ))),
+    }
+}
+
+/// Read a file on a separate thread, so a stale network mount or a FIFO
+/// cannot hang the caller
+///
+/// The thread is left behind when it times out.
+fn read_file(path: &Path, timeout: Duration) -> Result<Vec<u8>, DeviceInfoError> {
+    let source = path.to_string_lossy().to_string();
+    let (sender, receiver) = mpsc::channel();
+    let path = path.to_path_buf();
+    thread::spawn(move || sender.send(fs::read(path)));
+
+    match receiver.recv_timeout(timeout) {
+        Ok(content) => content.map_err(|e| DeviceInfoError::from_io(&source, e)),
+        Err(_) => Err(DeviceInfoError::ProbeFailed(format!(
+            "Reading {} timed out after {:?}",
+            source, timeout
+        ))),
+    }
+}
+
+fn read_env_var(name: &str) -> Result<Vec<u8>, DeviceInfoError> {
+    std::env::var_os(name)
+        .map(|value| value.to_string_lossy().as_bytes().to_vec())
+        .ok_or_else(|| DeviceInfoError::NotFound(format!("Environment variable {}", name)))
+}
+
+#[cfg(feature = "async")]
+async fn read_file_async(path: &Path, timeout: Duration) -> Result<Vec<u8>, DeviceInfoError> {
+    let source = path.to_string_lossy().to_string();
+    match tokio::time::timeout(timeout, tokio::fs::read(path)).await {
+        Ok(content) => content.map_err(|e| DeviceInfoError::from_io(&source, e)),
+        Err(_) => Err(DeviceInfoError::ProbeFailed(format!(
+            "Reading {} timed out after {:?}",
+            source, timeout
+        ))),
+    }
+}
+
+fn run_command(command: &[String], timeout: Duration) -> Result<Vec<u8>, DeviceInfoError> {
+    let line = command.join(" ");
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| DeviceInfoError::ProbeFailed("Empty command".to_string()))?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| DeviceInfoError::from_io(&line, e))?;
+
+    // Drain stdout while waiting, a full pipe would block the command
+    let mut stdout = child.stdout.take().unwrap();
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = sender.send(stdout.read_to_end(&mut output).map(|_| output));
+    });
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(10)),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(DeviceInfoError::ProbeFailed(format!(
+                    "{} timed out after {:?}",
+                    line, timeout
+                )));
+            }
+            Err(e) => return Err(DeviceInfoError::from_io(&line, e)),
+        }
+    };
+    if !status.success() {
+        return Err(DeviceInfoError::ProbeFailed(format!(
+            "{} exited with {}",
+            line, status
+        )));
+    }
+
+    // A background process left by the command can keep stdout open after
+    // it exits; the reader is then left behind
+    match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        Ok(output) => output.map_err(|e| DeviceInfoError::from_io(&line, e)),
+        Err(_) => Err(DeviceInfoError::ProbeFailed(format!(
+            "Reading the output of {} timed out after {:?}",
+            line, timeout
+        ))),
+    }
+}
+
+#[cfg(feature = "async")]
+async fn run_command_async(
+    command: &[String],
+    timeout: Duration,
+) -> Result<Vec<u8>, DeviceInfoError> {
+    let line = command.join(" ");
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| DeviceInfoError::ProbeFailed("Empty command".to_string()))?;
+    let child = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| DeviceInfoError::from_io(&line, e))?;
+
+    // On timeout the child is dropped with the future, which kills it
+    let output = match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(output) => output.map_err(|e| DeviceInfoError::from_io(&line, e))?,
+        Err(_) => {
+            return Err(DeviceInfoError::ProbeFailed(format!(
+                "{} timed out after {:?}",
+                line, timeout
+            )))
+        }
+    };
+    match output.status.success() {
+        true => Ok(output.stdout),
+        false => Err(DeviceInfoError::ProbeFailed(format!(
+            "{} exited with {}",
+            line, output.status
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(command: &[&str], hashed: bool) -> SourceComponents {
+        SourceComponents::CommandOutput {
+            command: command.iter().map(|arg| arg.to_string()).collect(),
+            hashed,
+        }
+    }
+
+    #[test]
+    fn test_as_string_round_trip() {
+        for source in [
+            SourceComponents::FileContents {
+                path: "/etc/app.conf".to_string(),
+                hashed: true,
+            },
+            SourceComponents::EnvVar {
+                name: "HOSTNAME".to_string(),
+                hashed: false,
+            },
+            command(&["vendor-tool", "--serial", "with space"], false),
+        ] {
+            assert_eq!(
+                SourceComponents::from_str(&source.as_string()),
+                Some(source)
+            );
+        }
+        assert_eq!(
+            command(&["uname", "-n"], true).as_string(),
+            r#"CommandSha256::["uname","-n"]"#
+        );
+        assert_eq!(SourceComponents::from_str("File::"), None);
+        assert_eq!(SourceComponents::from_str("Command::[]"), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_read_file() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        root.file("etc/app.conf", "tenant=acme\n");
+        let source = |hashed| SourceComponents::FileContents {
+            path: "/etc/app.conf".to_string(),
+            hashed,
+        };
+
+        assert_eq!(
+            source(false).read(root.path(), Duration::from_secs(5)),
+            Ok("tenant=acme".to_string())
+        );
+        assert_eq!(
+            source(true).read(root.path(), Duration::from_secs(5)),
+            Ok(hex::encode(Sha256::digest(b"tenant=acme")))
+        );
+        assert!(matches!(
+            SourceComponents::FileContents {
+                path: "/etc/missing".to_string(),
+                hashed: false,
+            }
+            .read(root.path(), Duration::from_secs(5)),
+            Err(DeviceInfoError::NotFound(_))
+        ));
+
+        // Relative paths follow the current directory, the crate root here
+        let relative = SourceComponents::FileContents {
+            path: "Cargo.toml".to_string(),
+            hashed: false,
+        };
+        assert!(relative
+            .read(Path::new("/"), Duration::from_secs(5))
+            .unwrap()
+            .starts_with("[package]"));
+        assert!(matches!(
+            relative.read(root.path(), Duration::from_secs(5)),
+            Err(DeviceInfoError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_run_command() {
+        let root = Path::new("/");
+        assert_eq!(
+            command(&["echo", "vendor serial"], false).read(root, Duration::from_secs(5)),
+            Ok("vendor serial".to_string())
+        );
+        assert!(matches!(
+            command(&["false"], false).read(root, Duration::from_secs(5)),
+            Err(DeviceInfoError::ProbeFailed(_))
+        ));
+        assert!(matches!(
+            command(&["sleep", "5"], false).read(root, Duration::from_millis(50)),
+            Err(DeviceInfoError::ProbeFailed(e)) if e.contains("timed out")
+        ));
+
+        // A background process keeping stdout open does not hold the read
+        let started = Instant::now();
+        assert!(matches!(
+            command(&["sh", "-c", "sleep 5 &"], false).read(root, Duration::from_millis(200)),
+            Err(DeviceInfoError::ProbeFailed(e)) if e.contains("timed out")
+        ));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    #[cfg(all(unix, feature = "async"))]
+    fn test_run_command_async() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let root = Path::new("/");
+        runtime.block_on(async {
+            assert_eq!(
+                command(&["echo", "vendor serial"], true)
+                    .read_async(root, Duration::from_secs(5))
+                    .await,
+                command(&["echo", "vendor serial"], true).read(root, Duration::from_secs(5))
+            );
+            assert!(matches!(
+                command(&["sleep", "5"], false)
+                    .read_async(root, Duration::from_millis(50))
+                    .await,
+                Err(DeviceInfoError::ProbeFailed(e)) if e.contains("timed out")
+            ));
+        });
+    }
+}
@@ -0,0 +1,170 @@
+use std::{collections::BTreeSet, fmt};
+
+use serde::{Deserialize, Serialize};
+
+/// Value of a component: a single string, or a list for facts that come in
+/// numbers (MAC addresses, disk or DIMM serials, ...)
+///
+/// Lists are kept sorted and deduplicated, so the enumeration order of the
+/// OS does not change the value. They serialize as JSON arrays.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ComponentValue {
+    Single(String),
+    List(Vec<String>),
+}
+
+impl ComponentValue {
+    /// Build a list value in canonical order
+    pub fn list<I, S>(values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        ComponentValue::List(
+            values
+                .into_iter()
+                .map(|value| value.into())
+                .collect::<BTreeSet<String>>()
+                .into_iter()
+                .collect(),
+        )
+    }
+
+    /// The values, a single value being a list of one
+    pub fn values(&self) -> Vec<&str> {
+        match self {
+            ComponentValue::Single(value) => vec![value.as_str()],
+            ComponentValue::List(values) => values.iter().map(|value| value.as_str()).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            ComponentValue::Single(value) => value.is_empty(),
+            ComponentValue::List(values) => values.is_empty(),
+        }
+    }
+
+    /// Add values to a list, turning a single value into a list
+    pub fn append(&mut self, other: ComponentValue) {
+        let values = self
+            .values()
+            .into_iter()
+            .chain(other.values())
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string())
+            .collect::<Vec<String>>();
+        *self = ComponentValue::list(values);
+    }
+
+    /// Compare two values, lists as sets
+    ///
+    /// Lists from machine codes issued before values were canonicalized may
+    /// be in any order or contain duplicates.
+    pub fn matches(&self, other: &ComponentValue) -> bool {
+        match (self, other) {
+            (ComponentValue::Single(a), ComponentValue::Single(b)) => a == b,
+            _ => {
+                self.values().into_iter().collect::<BTreeSet<&str>>()
+                    == other.values().into_iter().collect::<BTreeSet<&str>>()
+            }
+        }
+    }
+}
+
+impl Default for ComponentValue {
+    fn default() -> Self {
+        ComponentValue::Single(String::new())
+    }
+}
+
+impl fmt::Display for ComponentValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComponentValue::Single(value) => f.write_str(value),
+            ComponentValue::List(values) => f.write_str(&values.join(",")),
+        }
+    }
+}
+
+impl From<String> for ComponentValue {
+    fn from(value: String) -> Self {
+        ComponentValue::Single(value)
+    }
+}
+
+impl From<&str> for ComponentValue {
+    fn from(value: &str) -> Self {
+        ComponentValue::Single(value.to_string())
+    }
+}
+
+impl From<Vec<String>> for ComponentValue {
+    fn from(values: Vec<String>) -> Self {
+        ComponentValue::list(values)
+    }
+}
+
+impl PartialEq<str> for ComponentValue {
+    fn eq(&self, other: &str) -> bool {
+        matches!(self, ComponentValue::Single(value) if value == other)
+    }
+}
+
+impl PartialEq<&str> for ComponentValue {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_is_canonical() {
+        let value = ComponentValue::list(["b", "a", "b"]);
+
+        assert_eq!(value, ComponentValue::List(vec!["a".into(), "b".into()]));
+        assert_eq!(value.to_string(), "a,b");
+        assert_eq!(serde_json::to_string(&value).unwrap(), r#"["a","b"]"#);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        assert_eq!(
+            serde_json::from_str::<ComponentValue>(r#""abc""#).unwrap(),
+            "abc"
+        );
+        assert_eq!(
+            serde_json::from_str::<ComponentValue>(r#"["b","a"]"#).unwrap(),
+            ComponentValue::List(vec!["b".into(), "a".into()])
+        );
+    }
+
+    #[test]
+    fn test_matches_as_sets() {
+        let value = ComponentValue::list(["a", "b"]);
+
+        assert!(value.matches(&ComponentValue::List(vec![
+            "b".into(),
+            "a".into(),
+            "a".into()
+        ])));
+        assert!(!value.matches(&ComponentValue::list(["a"])));
+        assert!(ComponentValue::list(["a"]).matches(&"a".into()));
+        assert!(!ComponentValue::from("a").matches(&"b".into()));
+    }
+
+    #[test]
+    fn test_append() {
+        let mut value = ComponentValue::from("first");
+        value.append("second".into());
+        assert_eq!(value, ComponentValue::list(["first", "second"]));
+
+        let mut value = ComponentValue::default();
+        value.append(ComponentValue::list(["b", "a"]));
+        assert_eq!(value, ComponentValue::list(["a", "b"]));
+    }
+}
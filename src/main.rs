@@ -1,81 +1,136 @@
-use std::{env, io::Error};
+use std::{collections::BTreeMap, env, io::Error, path::Path};
 
-use crate::{
+use device_info::{
     core::builder::{IMainBuilder, MainDeviceInfoBuilder},
     core::crypto,
+    core::device_id::{DeviceIdFormatter, Encoding, HashAlgorithm},
     core::internal::IDeviceInfoBuilder,
-    plugins::{macos::plugin::IMacOSBuilder, windows::plugin::IWindowsBuilder},
+    core::profile::Profile,
+    plugins::linux::{plugin::ILinuxBuilder, virt::detect_virtualization},
 };
 
-mod core;
-mod plugins;
+/// Create a builder, re-rooted below `--sysroot` when given
+fn new_builder(m: &clap::ArgMatches) -> MainDeviceInfoBuilder {
+    let mut builder = MainDeviceInfoBuilder::new();
+    if let Some(sysroot) = m.get_one::<String>("sysroot") {
+        builder.set_sysroot(sysroot);
+    }
+    builder
+}
+
+/// The `--profile` of a subcommand
+fn profile(m: &clap::ArgMatches) -> Profile {
+    Profile::from_str(m.get_one::<String>("profile").unwrap()).unwrap()
+}
+
+fn profile_arg(default: &'static str) -> clap::Arg {
+    clap::Arg::new("profile")
+        .long("profile")
+        .default_value(default)
+        .value_parser(["licensing", "telemetry", "full"])
+        .help("Components to collect; a machine code only checks with the profile it was encrypted with")
+}
 
 /// Print device information
 ///
-/// Either print in json or text format
+/// Either print in json or text format, with `--verbose` where each value
+/// came from
 fn do_print(print_m: &clap::ArgMatches) {
-    let mut builder = MainDeviceInfoBuilder::new();
-    builder
-        .add_user_name()
-        .add_platform_name()
-        .add_device_name()
-        .add_cpu_arch()
-        .add_os_distro()
-        .on_windows(|windows_builder| {
-            windows_builder
-                .add_logon_user_name()
-                .add_system_drive_serial_number()
-                .add_mother_board_serial_number()
-                .add_system_uuid()
-                .add_mac_address()
-                .add_processor_id()
-                .add_machine_guid()
-        })
-        .on_macos(|macos_builder| {
-            macos_builder
-                .add_platform_serial_number()
-                .add_system_drive_serial_number()
-        });
+    let mut builder = new_builder(print_m);
+    builder.add_profile(profile(print_m));
 
+    let verbose = print_m.get_flag("verbose");
     match print_m
         .get_one::<String>("format")
         .map(|s| s.as_str())
         .unwrap()
     {
-        "json" => println!("{}", serde_json::to_string(&builder).unwrap()),
-        "text" => println!("{}", builder),
+        "json" => println!(
+            "{}",
+            serde_json::to_string(
+                builder
+                    .set_serialize_failures(true)
+                    .set_serialize_provenance(verbose)
+            )
+            .unwrap()
+        ),
+        "text" => {
+            println!("{}", builder);
+            if verbose {
+                println!("\nProvenance:");
+                let provenance: BTreeMap<_, _> = builder.get_provenance().iter().collect();
+                for (name, provenance) in provenance {
+                    println!(
+                        "{}: {} ({} confidence, {} ms, at {})",
+                        name,
+                        provenance.source,
+                        provenance.confidence,
+                        provenance.duration.as_millis(),
+                        provenance.collected_at
+                    );
+                }
+            }
+        }
         _ => panic!("Invalid format"),
     }
 }
 
-/// Encrypt specific device information into a machine code
+/// Warn about or refuse machine codes issued inside containers and VMs
 ///
-/// *NOTE*: It will collect different device information on different platforms
+/// Their identifiers are either ephemeral or shared between instances, so
+/// the machine code would not pin a single device.
+fn check_virtualization(policy: &str, sysroot: &Path) -> Result<(), Error> {
+    if policy == "allow" || (!cfg!(target_os = "linux") && sysroot == Path::new("/")) {
+        return Ok(());
+    }
+
+    let virtualization = detect_virtualization(sysroot);
+    match (virtualization.is_virtualized(), policy) {
+        (false, _) => Ok(()),
+        (true, "deny") => Err(Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!(
+                "Refusing to encrypt in a virtualized environment ({})",
+                virtualization
+            ),
+        )),
+        (true, _) => {
+            eprintln!(
+                "Warning: running in a virtualized environment ({}), the machine code may not be unique",
+                virtualization
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Add the components of `--profile` to a machine code or fingerprint
 ///
-/// On **Windows**, it will collect LogonUserName + SystemUuid + MotherBoardSerialNumber + SystemDriveSerialNumber
-/// On **MacOS**, it will collect UserName + PlatformSerialNumber + SystemDriveSerialNumber
-fn do_encrypt(encrypt_m: &clap::ArgMatches) {
-    let mut builder = MainDeviceInfoBuilder::new();
+/// *NOTE*: It will collect different device information on different platforms,
+/// see [`Profile::components`]
+///
+/// With `--session`, the Linux BootId is added as well, so the result stops
+/// matching after a reboot.
+fn add_machine_code_components(builder: &mut MainDeviceInfoBuilder, m: &clap::ArgMatches) {
+    builder.add_profile(profile(m));
+    if m.get_flag("session") {
+        builder.on_linux(|linux_builder| linux_builder.add_boot_id());
+    }
+}
+
+/// Encrypt specific device information into a machine code
+///
+/// See [`add_machine_code_components`] for the collected components.
+fn do_encrypt(encrypt_m: &clap::ArgMatches) -> Result<(), Error> {
+    let mut builder = new_builder(encrypt_m);
+    check_virtualization(
+        encrypt_m.get_one::<String>("virtualized").unwrap(),
+        builder.get_sysroot(),
+    )?;
+
     let key = encrypt_m.try_get_one::<String>("key").unwrap();
 
-    builder
-        .on_windows(|windows_builder| {
-            windows_builder
-                .add_logon_user_name()
-                .add_system_drive_serial_number()
-                .add_mother_board_serial_number()
-                .add_system_uuid()
-        })
-        .on_macos(|macos_builder| {
-            macos_builder
-                .add_platform_serial_number()
-                .add_system_drive_serial_number()
-        });
-
-    // add username if not windows
-    if !cfg!(target_os = "windows") {
-        builder.add_user_name();
-    }
+    add_machine_code_components(&mut builder, encrypt_m);
 
     let aes_key = crypto::aes::generate_aes_key(key);
 
@@ -83,6 +138,46 @@ fn do_encrypt(encrypt_m: &clap::ArgMatches) {
         Ok(encrypted) => println!("{}", encrypted),
         Err(e) => panic!("{}", e),
     }
+
+    Ok(())
+}
+
+/// Print a single fixed-length device ID
+///
+/// The components of the machine code are hashed instead of encrypted, so the
+/// ID can be compared as is but not decoded.
+fn do_fingerprint(fingerprint_m: &clap::ArgMatches) -> Result<(), Error> {
+    let mut builder = new_builder(fingerprint_m);
+    add_machine_code_components(&mut builder, fingerprint_m);
+
+    let algorithm = fingerprint_m.get_one::<String>("algorithm").unwrap();
+    let algorithm = match (
+        algorithm.as_str(),
+        fingerprint_m.try_get_one::<String>("key").unwrap(),
+    ) {
+        ("hmac-sha256", Some(key)) => HashAlgorithm::HmacSha256(key.as_bytes().to_vec()),
+        ("hmac-sha256", None) => match env::var("ENCRYPTION_KEY") {
+            Ok(key) => HashAlgorithm::HmacSha256(key.into_bytes()),
+            Err(_) => {
+                return Err(Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "hmac-sha256 needs --key or ENCRYPTION_KEY",
+                ))
+            }
+        },
+        (algorithm, _) => HashAlgorithm::from_str(algorithm).unwrap(),
+    };
+    let encoding =
+        Encoding::from_str(fingerprint_m.get_one::<String>("encoding").unwrap()).unwrap();
+
+    let mut formatter = DeviceIdFormatter::new();
+    formatter.set_algorithm(algorithm).set_encoding(encoding);
+    match formatter.format_builder(&builder) {
+        Ok(id) => println!("{}", id),
+        Err(e) => return Err(Error::new(std::io::ErrorKind::InvalidData, e.to_string())),
+    }
+
+    Ok(())
 }
 
 fn do_check(check_m: &clap::ArgMatches) -> Result<(), Error> {
@@ -92,34 +187,21 @@ fn do_check(check_m: &clap::ArgMatches) -> Result<(), Error> {
 
     match crypto::aes::decrypt(&aes_key, code) {
         Ok(decrypted) => {
-            let mut builder = MainDeviceInfoBuilder::new();
-            builder
-                .on_windows(|windows_builder| {
-                    windows_builder
-                        .add_logon_user_name()
-                        .add_system_drive_serial_number()
-                        .add_mother_board_serial_number()
-                        .add_system_uuid()
-                })
-                .on_macos(|macos_builder| {
-                    macos_builder
-                        .add_platform_serial_number()
-                        .add_system_drive_serial_number()
-                });
-
-            // add username if not windows
-            if !cfg!(target_os = "windows") {
-                builder.add_user_name();
-            }
+            let mut builder = new_builder(check_m);
+            add_machine_code_components(&mut builder, check_m);
 
             let deserialized: MainDeviceInfoBuilder = match serde_json::from_str(&decrypted) {
                 Ok(v) => v,
                 Err(_) => panic!(),
             };
 
-            // Compare components one by one
+            // Compare components one by one, lists as sets
             for (k, v) in builder.get_components() {
-                if deserialized.get_components().get(k) != Some(v) {
+                if !deserialized
+                    .get_components()
+                    .get(k)
+                    .is_some_and(|value| value.matches(v))
+                {
                     panic!();
                 }
             }
@@ -138,9 +220,16 @@ fn main() {
         .author(env!("CARGO_PKG_AUTHORS"))
         .version(env!("CARGO_PKG_VERSION"))
         .about("Device information CLI tool")
+        .arg(
+            clap::Arg::new("sysroot")
+                .long("sysroot")
+                .global(true)
+                .help("Read /proc, /sys and /etc below this directory instead of /"),
+        )
         .subcommand(
             clap::Command::new("print")
                 .about("Print device information")
+                .arg(profile_arg("full"))
                 .arg(
                     clap::Arg::new("format")
                         .short('f')
@@ -150,17 +239,38 @@ fn main() {
                         .value_parser(["json", "text"])
                         .ignore_case(true)
                         .help("output format, default json, support json, text"),
+                )
+                .arg(
+                    clap::Arg::new("verbose")
+                        .short('v')
+                        .long("verbose")
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Also show the source, time, duration and confidence of each value"),
                 ),
         )
         .subcommand(
             clap::Command::new("encrypt")
                 .about("Encrypt specific device information into a machine code")
+                .arg(profile_arg("licensing"))
                 .arg(
                     clap::Arg::new("key")
                         .short('k')
                         .long("key")
                         .help("Encryption key"),
                 )
+                .arg(
+                    clap::Arg::new("virtualized")
+                        .long("virtualized")
+                        .default_value("warn")
+                        .value_parser(["allow", "warn", "deny"])
+                        .help("What to do when running in a container or virtual machine"),
+                )
+                .arg(
+                    clap::Arg::new("session")
+                        .long("session")
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Also bind to the current boot session (Linux BootId)"),
+                )
                 .after_help(
                     "Note:\n\
                     * Set Env ENCRYPTION_KEY=YOUR_SECRET to encrypt the machine code.\n\
@@ -168,15 +278,55 @@ fn main() {
                     * The machine code will be used to verify if the device matches with the machine code.",
                 ),
         )
+        .subcommand(
+            clap::Command::new("fingerprint")
+                .about("Print a fixed-length device ID hashed from the machine code components")
+                .arg(profile_arg("licensing"))
+                .arg(
+                    clap::Arg::new("algorithm")
+                        .short('a')
+                        .long("algorithm")
+                        .default_value("sha256")
+                        .value_parser(["sha256", "sha1", "md5", "hmac-sha256"])
+                        .help("Hash algorithm"),
+                )
+                .arg(
+                    clap::Arg::new("encoding")
+                        .short('e')
+                        .long("encoding")
+                        .default_value("hex")
+                        .value_parser(["hex", "base32", "base64url"])
+                        .help("Encoding of the hash, base32 is Crockford's"),
+                )
+                .arg(
+                    clap::Arg::new("key")
+                        .short('k')
+                        .long("key")
+                        .help("HMAC key, defaults to Env ENCRYPTION_KEY"),
+                )
+                .arg(
+                    clap::Arg::new("session")
+                        .long("session")
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Also bind to the current boot session (Linux BootId)"),
+                ),
+        )
         .subcommand(
             clap::Command::new("check")
                 .about("Check if the device matches with the machine code")
+                .arg(profile_arg("licensing"))
                 .arg(
                     clap::Arg::new("key")
                         .short('k')
                         .long("key")
                         .help("Encryption key"),
                 )
+                .arg(
+                    clap::Arg::new("session")
+                        .long("session")
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Also bind to the current boot session (Linux BootId)"),
+                )
                 .arg(
                     clap::Arg::new("code")
                         .help("Code generated by encrypt command")
@@ -192,7 +342,18 @@ fn main() {
 
     match m.subcommand() {
         Some(("print", print_m)) => do_print(print_m),
-        Some(("encrypt", encrypt_m)) => do_encrypt(encrypt_m),
+        Some(("encrypt", encrypt_m)) => {
+            if let Err(e) = do_encrypt(encrypt_m) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(("fingerprint", fingerprint_m)) => {
+            if let Err(e) = do_fingerprint(fingerprint_m) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
         Some(("check", check_m)) => match do_check(check_m) {
             Ok(_) => println!("0"),
             Err(_) => println!("1"),
@@ -0,0 +1,421 @@
+//! Resolve the physical disk that backs the root filesystem
+//!
+//! The root mount is looked up in `/proc/self/mountinfo`, then the block
+//! device is followed through `/sys/class/block`: partitions lead to their
+//! parent disk, device-mapper (LVM, LUKS) and md RAID devices lead to their
+//! `slaves`. The first physical disk reached reports its serial, or its WWN
+//! when no serial is exposed.
+//!
+//! Every path is taken relative to a filesystem root so the walk can run
+//! against a copied or fake tree.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Root mount entry taken from `mountinfo`
+#[derive(Debug, PartialEq, Eq)]
+pub struct MountInfo {
+    pub major_minor: String,
+    pub fs_type: String,
+    pub source: String,
+}
+
+/// Find the entry mounted at `mount_point` in `mountinfo` content
+///
+/// Later entries shadow earlier ones, so the last match wins.
+pub fn parse_mountinfo(content: &str, mount_point: &str) -> Option<MountInfo> {
+    content.lines().rev().find_map(|line| {
+        let (mount, fs) = line.split_once(" - ")?;
+        let mount_fields: Vec<&str> = mount.split_whitespace().collect();
+        let mut fs_fields = fs.split_whitespace();
+
+        if unescape(mount_fields.get(4)?) != mount_point {
+            return None;
+        }
+
+        Some(MountInfo {
+            major_minor: mount_fields.get(2)?.to_string(),
+            fs_type: fs_fields.next()?.to_string(),
+            source: unescape(fs_fields.next().unwrap_or("")),
+        })
+    })
+}
+
+/// Undo the octal escaping (`\040` for space, ...) used in `mountinfo`
+fn unescape(field: &str) -> String {
+    // Escaped bytes can be parts of a multi-byte UTF-8 character
+    let mut result = Vec::with_capacity(field.len());
+    let mut rest = field;
+    while let Some(index) = rest.find('\\') {
+        result.extend_from_slice(&rest.as_bytes()[..index]);
+        let escaped = rest.get(index + 1..index + 4).unwrap_or("");
+        match u8::from_str_radix(escaped, 8) {
+            Ok(byte) if escaped.len() == 3 => {
+                result.push(byte);
+                rest = &rest[index + 4..];
+            }
+            _ => {
+                result.push(b'\\');
+                rest = &rest[index + 1..];
+            }
+        }
+    }
+    result.extend_from_slice(rest.as_bytes());
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty())
+}
+
+fn file_name(path: &Path) -> Option<String> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.to_string())
+}
+
+/// Walks block devices below `<root>/sys` and reads `<root>/proc`
+pub struct BlockDeviceResolver {
+    root: PathBuf,
+}
+
+impl BlockDeviceResolver {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+        }
+    }
+
+    fn class_block(&self, name: &str) -> PathBuf {
+        self.root.join("sys/class/block").join(name)
+    }
+
+    /// Block device name (`sda2`, `dm-0`, ...) holding the root filesystem
+    pub fn root_device(&self) -> Option<String> {
+        let mountinfo = fs::read_to_string(self.root.join("proc/self/mountinfo")).ok()?;
+        let mount = parse_mountinfo(&mountinfo, "/")?;
+
+        // `/sys/dev/block/<major>:<minor>` links to the device directory.
+        // Filesystems like btrfs report an anonymous `0:<n>` device instead,
+        // in which case the mount source is used.
+        let by_number = self.root.join("sys/dev/block").join(&mount.major_minor);
+        if let Some(name) = fs::canonicalize(by_number)
+            .ok()
+            .and_then(|path| file_name(&path))
+        {
+            return Some(name);
+        }
+
+        self.device_from_source(&mount.source)
+    }
+
+    fn device_from_source(&self, source: &str) -> Option<String> {
+        let source = source.strip_prefix("/dev/")?;
+
+        if let Some(mapper_name) = source.strip_prefix("mapper/") {
+            return fs::read_dir(self.root.join("sys/class/block"))
+                .ok()?
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .find(|name| {
+                    read_trimmed(&self.class_block(name).join("dm/name")).as_deref()
+                        == Some(mapper_name)
+                });
+        }
+
+        let name = file_name(Path::new(source))?;
+        self.class_block(&name).exists().then_some(name)
+    }
+
+    /// Physical disks reached from `name` through partitions and `slaves`
+    ///
+    /// The result is sorted, so RAID members are always visited in the
+    /// same order.
+    pub fn physical_disks(&self, name: &str) -> Vec<String> {
+        let device = self.class_block(name);
+
+        if device.join("partition").exists() {
+            return fs::canonicalize(&device)
+                .ok()
+                .and_then(|path| path.parent().and_then(file_name))
+                .map(|disk| self.physical_disks(&disk))
+                .unwrap_or_default();
+        }
+
+        let mut slaves: Vec<String> = fs::read_dir(device.join("slaves"))
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter_map(|entry| entry.file_name().into_string().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if slaves.is_empty() {
+            return match device.exists() {
+                true => vec![name.to_string()],
+                false => vec![],
+            };
+        }
+
+        slaves.sort();
+        let mut disks: Vec<String> = slaves
+            .iter()
+            .flat_map(|slave| self.physical_disks(slave))
+            .collect();
+        disks.sort();
+        disks.dedup();
+        disks
+    }
+
+    /// Serial number of a whole disk, or its WWN when there is none
+    pub fn disk_serial_number(&self, disk: &str) -> Option<String> {
+        let device = self.class_block(disk);
+        let udev = self.udev_properties(disk);
+
+        read_trimmed(&device.join("device/serial"))
+            .or_else(|| read_trimmed(&device.join("serial")))
+            .or_else(|| udev_property(&udev, "ID_SERIAL_SHORT"))
+            .or_else(|| {
+                fs::read(device.join("device/vpd_pg80"))
+                    .ok()
+                    .and_then(|page| parse_vpd_serial(&page))
+            })
+            .or_else(|| read_trimmed(&device.join("wwid")))
+            .or_else(|| read_trimmed(&device.join("device/wwid")))
+            .or_else(|| udev_property(&udev, "ID_WWN"))
+    }
+
+    fn udev_properties(&self, disk: &str) -> String {
+        read_trimmed(&self.class_block(disk).join("dev"))
+            .and_then(|major_minor| {
+                fs::read_to_string(
+                    self.root
+                        .join("run/udev/data")
+                        .join(format!("b{}", major_minor)),
+                )
+                .ok()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Serial number (or WWN) of the first physical disk backing `/`
+    pub fn system_drive_serial_number(&self) -> Option<String> {
+        let root_device = self.root_device()?;
+        self.physical_disks(&root_device)
+            .iter()
+            .find_map(|disk| self.disk_serial_number(disk))
+    }
+}
+
+fn udev_property(content: &str, key: &str) -> Option<String> {
+    content
+        .lines()
+        .filter_map(|line| line.strip_prefix("E:"))
+        .filter_map(|line| line.split_once('='))
+        .find(|(name, _)| *name == key)
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Decode a SCSI "Unit Serial Number" VPD page (0x80)
+fn parse_vpd_serial(page: &[u8]) -> Option<String> {
+    if page.len() < 4 || page[1] != 0x80 {
+        return None;
+    }
+    let length = u16::from_be_bytes([page[2], page[3]]) as usize;
+    let serial = page.get(4..4 + length)?;
+    let serial = String::from_utf8_lossy(serial).trim().to_string();
+    (!serial.is_empty()).then_some(serial)
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use super::*;
+    use crate::plugins::linux::fake_root::FakeRoot;
+
+    const PCI: &str = "../../devices/pci0000:00/0000:00:17.0";
+
+    fn mountinfo(major_minor: &str, fs_type: &str, source: &str) -> String {
+        format!(
+            "22 1 0:21 / /proc rw,nosuid - proc proc rw\n\
+             28 1 {} / / rw,relatime shared:1 - {} {} rw\n\
+             30 28 0:25 / /run rw,nosuid shared:5 - tmpfs tmpfs rw\n",
+            major_minor, fs_type, source
+        )
+    }
+
+    /// Whole disk `name` with a partition `<name><suffix>`
+    fn add_disk(root: &FakeRoot, name: &str, partition: &str, major_minor: &str) {
+        root.symlink(
+            &format!("sys/class/block/{}", name),
+            &format!("{}/block/{}", PCI, name),
+        )
+        .symlink(
+            &format!("sys/class/block/{}", partition),
+            &format!("{}/block/{}/{}", PCI, name, partition),
+        )
+        .file(
+            &format!("sys/devices/pci0000:00/0000:00:17.0/block/{}/dev", name),
+            major_minor,
+        )
+        .file(
+            &format!(
+                "sys/devices/pci0000:00/0000:00:17.0/block/{}/{}/partition",
+                name, partition
+            ),
+            "2\n",
+        );
+    }
+
+    fn add_virtual(root: &FakeRoot, name: &str, slaves: &[&str]) {
+        root.symlink(
+            &format!("sys/class/block/{}", name),
+            &format!("../../devices/virtual/block/{}", name),
+        );
+        for slave in slaves {
+            root.dir(&format!(
+                "sys/devices/virtual/block/{}/slaves/{}",
+                name, slave
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_mountinfo() {
+        let content = mountinfo("8:2", "ext4", "/dev/sda2")
+            + "40 28 8:3 / / rw - xfs /dev/with\\040space rw\n";
+        assert_eq!(
+            parse_mountinfo(&content, "/"),
+            Some(MountInfo {
+                major_minor: "8:3".to_string(),
+                fs_type: "xfs".to_string(),
+                source: "/dev/with space".to_string(),
+            })
+        );
+        assert_eq!(parse_mountinfo(&content, "/home"), None);
+
+        // Escaped bytes of a multi-byte character
+        assert_eq!(unescape("/mnt/\\303\\251t\\303\\251"), "/mnt/été");
+        assert_eq!(unescape("/mnt/back\\slash"), "/mnt/back\\slash");
+    }
+
+    #[test]
+    fn test_partition_on_sata_disk() {
+        let root = FakeRoot::new();
+        root.file("proc/self/mountinfo", mountinfo("8:2", "ext4", "/dev/sda2"));
+        add_disk(&root, "sda", "sda2", "8:0");
+        root.symlink("sys/dev/block/8:2", "../../block/sda/sda2")
+            .symlink(
+                "sys/block/sda",
+                "../devices/pci0000:00/0000:00:17.0/block/sda",
+            )
+            .file(
+                "run/udev/data/b8:0",
+                "S:disk/by-id/ata-X\nE:ID_SERIAL_SHORT=S3Z9NB0K\n",
+            );
+
+        let resolver = BlockDeviceResolver::new(root.path());
+        assert_eq!(resolver.root_device(), Some("sda2".to_string()));
+        assert_eq!(resolver.physical_disks("sda2"), vec!["sda".to_string()]);
+        assert_eq!(
+            resolver.system_drive_serial_number(),
+            Some("S3Z9NB0K".to_string())
+        );
+    }
+
+    #[test]
+    fn test_lvm_on_luks_on_nvme() {
+        let root = FakeRoot::new();
+        root.file(
+            "proc/self/mountinfo",
+            mountinfo("253:1", "ext4", "/dev/mapper/vg-root"),
+        );
+        add_disk(&root, "nvme0n1", "nvme0n1p3", "259:0");
+        add_virtual(&root, "dm-0", &["nvme0n1p3"]);
+        add_virtual(&root, "dm-1", &["dm-0"]);
+        root.file("sys/devices/virtual/block/dm-1/dm/name", "vg-root\n")
+            .symlink("sys/dev/block/253:1", "../../devices/virtual/block/dm-1")
+            .file(
+                "sys/devices/pci0000:00/0000:00:17.0/block/nvme0n1/device/serial",
+                "PHNH912300ABC     \n",
+            );
+
+        let resolver = BlockDeviceResolver::new(root.path());
+        assert_eq!(resolver.physical_disks("dm-1"), vec!["nvme0n1".to_string()]);
+        assert_eq!(
+            resolver.system_drive_serial_number(),
+            Some("PHNH912300ABC".to_string())
+        );
+    }
+
+    #[test]
+    fn test_md_raid_prefers_sorted_members() {
+        let root = FakeRoot::new();
+        root.file("proc/self/mountinfo", mountinfo("9:0", "ext4", "/dev/md0"));
+        add_disk(&root, "sdb", "sdb1", "8:16");
+        add_disk(&root, "sda", "sda1", "8:0");
+        add_virtual(&root, "md0", &["sdb1", "sda1"]);
+        root.symlink("sys/dev/block/9:0", "../../devices/virtual/block/md0")
+            .file(
+                "sys/devices/pci0000:00/0000:00:17.0/block/sda/wwid",
+                "naa.5000c500a1b2c3d4\n",
+            )
+            .file(
+                "sys/devices/pci0000:00/0000:00:17.0/block/sdb/wwid",
+                "naa.5000c500ffffffff\n",
+            );
+
+        let resolver = BlockDeviceResolver::new(root.path());
+        assert_eq!(
+            resolver.physical_disks("md0"),
+            vec!["sda".to_string(), "sdb".to_string()]
+        );
+        assert_eq!(
+            resolver.system_drive_serial_number(),
+            Some("naa.5000c500a1b2c3d4".to_string())
+        );
+    }
+
+    #[test]
+    fn test_btrfs_falls_back_on_source() {
+        let root = FakeRoot::new();
+        root.file(
+            "proc/self/mountinfo",
+            mountinfo("0:31", "btrfs", "/dev/sda2"),
+        );
+        add_disk(&root, "sda", "sda2", "8:0");
+        let mut page = vec![0x00, 0x80, 0x00, 0x0a];
+        page.extend_from_slice(b"  WD-12345");
+        root.file(
+            "sys/devices/pci0000:00/0000:00:17.0/block/sda/device/vpd_pg80",
+            page,
+        );
+
+        let resolver = BlockDeviceResolver::new(root.path());
+        assert_eq!(resolver.root_device(), Some("sda2".to_string()));
+        assert_eq!(
+            resolver.system_drive_serial_number(),
+            Some("WD-12345".to_string())
+        );
+    }
+
+    #[test]
+    fn test_overlay_root_has_no_disk() {
+        let root = FakeRoot::new();
+        root.file(
+            "proc/self/mountinfo",
+            mountinfo("0:52", "overlay", "overlay"),
+        );
+
+        let resolver = BlockDeviceResolver::new(root.path());
+        assert_eq!(resolver.root_device(), None);
+        assert_eq!(resolver.system_drive_serial_number(), None);
+    }
+}
@@ -0,0 +1,75 @@
+use std::{fs, path::Path};
+
+/// Random id generated by the kernel on every boot
+pub fn read_boot_id(root: &Path) -> Option<String> {
+    fs::read_to_string(root.join("proc/sys/kernel/random/boot_id"))
+        .ok()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// Boot time in seconds since the epoch, from the `btime` line of `/proc/stat`
+///
+/// The kernel derives it from the wall clock, so it moves when the clock is
+/// stepped; `boot_id` is the reliable way to tell boots apart.
+pub fn parse_boot_time(stat: &str) -> Option<u64> {
+    stat.lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|value| value.trim().parse().ok())
+}
+
+/// Whole seconds since boot, from the first field of `/proc/uptime`
+pub fn parse_uptime(uptime: &str) -> Option<u64> {
+    uptime
+        .split_whitespace()
+        .next()
+        .and_then(|seconds| seconds.parse::<f64>().ok())
+        .map(|seconds| seconds as u64)
+}
+
+pub fn read_boot_time(root: &Path) -> Option<u64> {
+    parse_boot_time(&fs::read_to_string(root.join("proc/stat")).ok()?)
+}
+
+pub fn read_uptime(root: &Path) -> Option<u64> {
+    parse_uptime(&fs::read_to_string(root.join("proc/uptime")).ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_boot_time() {
+        let stat = "cpu  2255 34 2290 22625563 6290 127 456 0 0 0\n\
+                    intr 114930548 113199788 3 0 5 263 0 4 [...]\n\
+                    ctxt 1990473\n\
+                    btime 1062191376\n\
+                    processes 2915\n";
+
+        assert_eq!(parse_boot_time(stat), Some(1062191376));
+        assert_eq!(parse_boot_time("ctxt 1990473\n"), None);
+    }
+
+    #[test]
+    fn test_parse_uptime() {
+        assert_eq!(parse_uptime("350735.47 234388.90\n"), Some(350735));
+        assert_eq!(parse_uptime(""), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_read_boot_id() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        assert_eq!(read_boot_id(root.path()), None);
+
+        root.file(
+            "proc/sys/kernel/random/boot_id",
+            "5d1a1b5e-7b42-4b4e-9a0c-1f7f0a4a7c55\n",
+        );
+        assert_eq!(
+            read_boot_id(root.path()),
+            Some("5d1a1b5e-7b42-4b4e-9a0c-1f7f0a4a7c55".to_string())
+        );
+    }
+}
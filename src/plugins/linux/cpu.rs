@@ -0,0 +1,239 @@
+use std::{collections::BTreeSet, fs, path::Path};
+
+use sha2::{Digest, Sha256};
+
+/// CPU identity of the machine
+///
+/// Identification fields are taken from the first processor entry of
+/// `/proc/cpuinfo`; on ARM, where there is no vendor string or family, the
+/// implementer, part and revision fill the same slots.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CpuInfo {
+    pub vendor: String,
+    pub model_name: String,
+    pub family: String,
+    pub model: String,
+    pub stepping: String,
+    pub microcode: String,
+    pub sockets: usize,
+    pub cores: usize,
+    pub threads: usize,
+    pub flags: BTreeSet<String>,
+}
+
+impl CpuInfo {
+    /// SHA-256 over the sorted feature flags, hex encoded
+    ///
+    /// Flags are sorted and deduplicated first, so the hash only changes when
+    /// the feature set does. Empty when no flags were read.
+    pub fn flags_hash(&self) -> String {
+        if self.flags.is_empty() {
+            return String::new();
+        }
+        let flags = self
+            .flags
+            .iter()
+            .cloned()
+            .collect::<Vec<String>>()
+            .join(" ");
+        hex::encode(Sha256::digest(flags.as_bytes()))
+    }
+}
+
+fn field<'a>(block: &'a str, names: &[&str]) -> Option<&'a str> {
+    block.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        names
+            .contains(&key.trim())
+            .then_some(value.trim())
+            .filter(|value| !value.is_empty())
+    })
+}
+
+/// Parse `/proc/cpuinfo` content
+///
+/// The topology is counted from the `physical id` and `core id` fields,
+/// which are missing on most ARM kernels: every processor then counts as a
+/// core of a single socket.
+pub fn parse_cpuinfo(content: &str) -> CpuInfo {
+    let processors: Vec<&str> = content
+        .split("\n\n")
+        .filter(|block| field(block, &["processor"]).is_some())
+        .collect();
+    let first = processors.first().copied().unwrap_or("");
+    let text = |names: &[&str]| field(first, names).unwrap_or("").to_string();
+
+    let topology: Vec<(&str, &str)> = processors
+        .iter()
+        .filter_map(|block| {
+            let socket = field(block, &["physical id"])?;
+            Some((socket, field(block, &["core id"])?))
+        })
+        .collect();
+
+    CpuInfo {
+        vendor: text(&["vendor_id", "CPU implementer"]),
+        model_name: text(&["model name", "Processor", "cpu model"]),
+        family: text(&["cpu family", "CPU architecture"]),
+        model: text(&["model", "CPU part"]),
+        stepping: text(&["stepping", "CPU revision"]),
+        microcode: text(&["microcode"]),
+        sockets: match topology.is_empty() {
+            true => usize::from(!processors.is_empty()),
+            false => count_distinct(topology.iter().map(|(socket, _)| *socket)),
+        },
+        cores: match topology.is_empty() {
+            true => processors.len(),
+            false => count_distinct(topology.iter()),
+        },
+        threads: processors.len(),
+        flags: field(first, &["flags", "Features"])
+            .unwrap_or("")
+            .split_whitespace()
+            .map(|flag| flag.to_string())
+            .collect(),
+    }
+}
+
+fn count_distinct<T: Ord>(items: impl Iterator<Item = T>) -> usize {
+    items.collect::<BTreeSet<T>>().len()
+}
+
+/// Count sockets, cores and threads from `/sys/devices/system/cpu/cpu*/topology`
+///
+/// Returns `None` when the topology is not exposed.
+fn read_topology(cpu_root: &Path) -> Option<(usize, usize, usize)> {
+    let packages: Vec<(String, String)> = fs::read_dir(cpu_root)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            let index = name.strip_prefix("cpu").unwrap_or("");
+            !index.is_empty() && index.chars().all(|c| c.is_ascii_digit())
+        })
+        .filter_map(|entry| {
+            let topology = entry.path().join("topology");
+            let read = |name: &str| {
+                fs::read_to_string(topology.join(name))
+                    .ok()
+                    .map(|value| value.trim().to_string())
+            };
+            Some((read("physical_package_id")?, read("core_id")?))
+        })
+        .collect();
+
+    match packages.is_empty() {
+        true => None,
+        false => Some((
+            count_distinct(packages.iter().map(|(package, _)| package)),
+            count_distinct(packages.iter()),
+            packages.len(),
+        )),
+    }
+}
+
+/// Read `<root>/proc/cpuinfo`, with the topology from sysfs when available
+pub fn read_cpu_info(root: &Path) -> Option<CpuInfo> {
+    let content = fs::read_to_string(root.join("proc/cpuinfo")).ok()?;
+    let mut cpu_info = parse_cpuinfo(&content);
+
+    if let Some((sockets, cores, threads)) = read_topology(&root.join("sys/devices/system/cpu")) {
+        cpu_info.sockets = sockets;
+        cpu_info.cores = cores;
+        cpu_info.threads = threads;
+    }
+
+    Some(cpu_info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CPUINFO_X86_64: &str = include_str!("fixtures/cpuinfo_x86_64.txt");
+    const CPUINFO_AARCH64: &str = include_str!("fixtures/cpuinfo_aarch64.txt");
+
+    #[test]
+    fn test_parse_cpuinfo_x86_64() {
+        let cpu_info = parse_cpuinfo(CPUINFO_X86_64);
+
+        assert_eq!(cpu_info.vendor, "GenuineIntel");
+        assert_eq!(
+            cpu_info.model_name,
+            "Intel(R) Core(TM) i7-8700 CPU @ 3.20GHz"
+        );
+        assert_eq!(
+            (
+                cpu_info.family.as_str(),
+                cpu_info.model.as_str(),
+                cpu_info.stepping.as_str()
+            ),
+            ("6", "158", "10")
+        );
+        assert_eq!(cpu_info.microcode, "0xf4");
+        assert_eq!(
+            (cpu_info.sockets, cpu_info.cores, cpu_info.threads),
+            (1, 2, 4)
+        );
+        assert!(cpu_info.flags.contains("avx2"));
+    }
+
+    #[test]
+    fn test_parse_cpuinfo_aarch64() {
+        let cpu_info = parse_cpuinfo(CPUINFO_AARCH64);
+
+        assert_eq!(cpu_info.vendor, "0x41");
+        assert_eq!(cpu_info.model, "0xd0c");
+        assert_eq!(cpu_info.stepping, "1");
+        assert_eq!(cpu_info.microcode, "");
+        assert_eq!(
+            (cpu_info.sockets, cpu_info.cores, cpu_info.threads),
+            (1, 2, 2)
+        );
+        assert!(cpu_info.flags.contains("asimd"));
+    }
+
+    #[test]
+    fn test_flags_hash_ignores_order() {
+        let cpu_info = parse_cpuinfo(CPUINFO_X86_64);
+        let reordered = parse_cpuinfo(&CPUINFO_X86_64.replace("sse sse2", "sse2 sse"));
+
+        assert_eq!(cpu_info.flags_hash().len(), 64);
+        assert_eq!(cpu_info.flags_hash(), reordered.flags_hash());
+        assert_ne!(
+            cpu_info.flags_hash(),
+            parse_cpuinfo(CPUINFO_AARCH64).flags_hash()
+        );
+
+        // Without flags there is no feature set to identify
+        assert_eq!(parse_cpuinfo("processor\t: 0\n").flags_hash(), "");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_read_cpu_info_prefers_sysfs_topology() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        root.file("proc/cpuinfo", CPUINFO_AARCH64);
+        for (cpu, package, core) in [(0, 0, 0), (1, 0, 1), (2, 1, 0), (3, 1, 1)] {
+            root.file(
+                &format!(
+                    "sys/devices/system/cpu/cpu{}/topology/physical_package_id",
+                    cpu
+                ),
+                format!("{}\n", package),
+            )
+            .file(
+                &format!("sys/devices/system/cpu/cpu{}/topology/core_id", cpu),
+                format!("{}\n", core),
+            );
+        }
+        root.dir("sys/devices/system/cpu/cpufreq");
+
+        let cpu_info = read_cpu_info(root.path()).unwrap();
+        assert_eq!(
+            (cpu_info.sockets, cpu_info.cores, cpu_info.threads),
+            (2, 4, 4)
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Throwaway directory tree standing in for `/` in probe tests
+///
+/// Removed again when dropped.
+pub struct FakeRoot {
+    path: PathBuf,
+}
+
+impl FakeRoot {
+    pub fn new() -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "device-info-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn file(&self, relative: &str, content: impl AsRef<[u8]>) -> &Self {
+        let path = self.path.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+        self
+    }
+
+    pub fn dir(&self, relative: &str) -> &Self {
+        fs::create_dir_all(self.path.join(relative)).unwrap();
+        self
+    }
+
+    pub fn symlink(&self, relative: &str, target: &str) -> &Self {
+        let path = self.path.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::os::unix::fs::symlink(target, path).unwrap();
+        self
+    }
+}
+
+impl Drop for FakeRoot {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
@@ -0,0 +1,38 @@
+use std::{fs, path::Path};
+
+use crate::core::error::DeviceInfoError;
+use crate::core::memory::{memory_modules_from_smbios, MemoryModule};
+use crate::core::smbios::SmbiosTables;
+
+/// Total memory in bytes, from the `MemTotal` line of `/proc/meminfo`
+pub fn parse_mem_total(meminfo: &str) -> Option<u64> {
+    meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))
+        .and_then(|value| value.trim().strip_suffix("kB"))
+        .and_then(|kilobytes| kilobytes.trim().parse::<u64>().ok())
+        .map(|kilobytes| kilobytes * 1024)
+}
+
+pub fn read_mem_total(root: &Path) -> Option<u64> {
+    parse_mem_total(&fs::read_to_string(root.join("proc/meminfo")).ok()?)
+}
+
+pub fn list_memory_modules(root: &Path) -> Result<Vec<MemoryModule>, DeviceInfoError> {
+    SmbiosTables::from_sysfs(root).map(|tables| memory_modules_from_smbios(&tables))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mem_total() {
+        let meminfo = "MemTotal:       16301036 kB\n\
+                       MemFree:         1221472 kB\n\
+                       MemAvailable:    9874412 kB\n";
+
+        assert_eq!(parse_mem_total(meminfo), Some(16301036 * 1024));
+        assert_eq!(parse_mem_total("MemFree: 1221472 kB\n"), None);
+    }
+}
@@ -0,0 +1,11 @@
+pub mod block;
+pub mod boot;
+pub mod cpu;
+pub mod memory;
+pub mod network;
+pub mod os_release;
+pub mod plugin;
+pub mod virt;
+
+#[cfg(all(test, unix))]
+pub(crate) mod fake_root;
@@ -0,0 +1,162 @@
+use std::{fs, path::Path};
+
+use crate::core::network::{NetworkAdapter, NetworkAdapterKind};
+
+/// `ARPHRD_ETHER`, the only link type carrying a real MAC address
+const ARPHRD_ETHER: &str = "1";
+
+/// Interface name prefixes of software devices
+///
+/// Checked on top of the `device` symlink, which some hypervisor and VPN
+/// drivers do create.
+const VIRTUAL_PREFIXES: [&str; 10] = [
+    "veth", "docker", "br-", "virbr", "vnet", "tun", "tap", "wg", "vmnet", "vboxnet",
+];
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty())
+}
+
+fn adapter_kind(interface: &Path, name: &str) -> NetworkAdapterKind {
+    let is_virtual = !interface.join("device").exists()
+        || interface.join("bridge").exists()
+        || interface.join("tun_flags").exists()
+        || VIRTUAL_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix));
+
+    match is_virtual {
+        true => NetworkAdapterKind::Virtual,
+        false if interface.join("wireless").exists() || interface.join("phy80211").exists() => {
+            NetworkAdapterKind::Wireless
+        }
+        false => NetworkAdapterKind::Physical,
+    }
+}
+
+/// Read `<root>/sys/class/net/*`
+///
+/// A bonding slave reports the MAC of its bond, so its permanent address is
+/// taken from `bonding_slave/perm_hwaddr` instead.
+pub fn list_network_adapters(root: &Path) -> Vec<NetworkAdapter> {
+    let mut adapters: Vec<NetworkAdapter> = fs::read_dir(root.join("sys/class/net"))
+        .map(|entries| entries.filter_map(|entry| entry.ok()).collect::<Vec<_>>())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let interface = entry.path();
+
+            if read_trimmed(&interface.join("type")).as_deref() != Some(ARPHRD_ETHER) {
+                return None;
+            }
+
+            let mac_address = read_trimmed(&interface.join("bonding_slave/perm_hwaddr"))
+                .or_else(|| read_trimmed(&interface.join("address")))
+                .filter(|address| address.chars().any(|c| c.is_ascii_hexdigit() && c != '0'))?;
+
+            let driver = fs::read_link(interface.join("device/driver"))
+                .ok()
+                .and_then(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .map(|name| name.to_string())
+                });
+
+            Some(NetworkAdapter {
+                kind: adapter_kind(&interface, &name),
+                name,
+                mac_address,
+                driver,
+            })
+        })
+        .collect();
+
+    adapters.sort_by(|a, b| a.name.cmp(&b.name));
+    adapters
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use super::*;
+    use crate::core::network::physical_mac_addresses;
+    use crate::plugins::linux::fake_root::FakeRoot;
+
+    fn add_interface(root: &FakeRoot, name: &str, mac_address: &str, pci: Option<&str>) {
+        let device = match pci {
+            Some(pci) => format!("sys/devices/pci0000:00/{}/net/{}", pci, name),
+            None => format!("sys/devices/virtual/net/{}", name),
+        };
+        root.symlink(
+            &format!("sys/class/net/{}", name),
+            &format!("../../{}", device.trim_start_matches("sys/")),
+        )
+        .file(&format!("{}/address", device), format!("{}\n", mac_address))
+        .file(&format!("{}/type", device), "1\n");
+        if let Some(pci) = pci {
+            root.symlink(&format!("{}/device", device), "../..")
+                .symlink(
+                    &format!("sys/devices/pci0000:00/{}/driver", pci),
+                    "../../../bus/pci/drivers/e1000e",
+                );
+        }
+    }
+
+    #[test]
+    fn test_list_network_adapters() {
+        let root = FakeRoot::new();
+        add_interface(
+            &root,
+            "enp0s31f6",
+            "b4:2e:99:00:00:01",
+            Some("0000:00:1f.6"),
+        );
+        add_interface(&root, "wlp2s0", "b4:2e:99:00:00:02", Some("0000:02:00.0"));
+        root.dir("sys/devices/pci0000:00/0000:02:00.0/net/wlp2s0/wireless");
+        add_interface(&root, "docker0", "02:42:ac:11:00:01", None);
+        root.dir("sys/devices/virtual/net/docker0/bridge");
+        add_interface(&root, "veth12ab", "6a:00:00:00:00:01", None);
+        add_interface(&root, "lo", "00:00:00:00:00:00", None);
+        root.file("sys/devices/virtual/net/lo/type", "772\n");
+
+        let adapters = list_network_adapters(root.path());
+        let kinds: Vec<(&str, NetworkAdapterKind)> = adapters
+            .iter()
+            .map(|adapter| (adapter.name.as_str(), adapter.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("docker0", NetworkAdapterKind::Virtual),
+                ("enp0s31f6", NetworkAdapterKind::Physical),
+                ("veth12ab", NetworkAdapterKind::Virtual),
+                ("wlp2s0", NetworkAdapterKind::Wireless),
+            ]
+        );
+        assert_eq!(adapters[1].driver, Some("e1000e".to_string()));
+        assert_eq!(
+            physical_mac_addresses(&adapters),
+            vec![
+                "b4:2e:99:00:00:01".to_string(),
+                "b4:2e:99:00:00:02".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bonding_slave_uses_permanent_address() {
+        let root = FakeRoot::new();
+        add_interface(&root, "eno1", "b4:2e:99:00:00:09", Some("0000:00:19.0"));
+        root.file(
+            "sys/devices/pci0000:00/0000:00:19.0/net/eno1/bonding_slave/perm_hwaddr",
+            "b4:2e:99:00:00:01\n",
+        );
+
+        let adapters = list_network_adapters(root.path());
+        assert_eq!(adapters[0].mac_address, "b4:2e:99:00:00:01");
+    }
+}
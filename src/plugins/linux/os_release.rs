@@ -0,0 +1,152 @@
+use std::{collections::HashMap, fs, path::Path};
+
+const OS_RELEASE_PATHS: [&str; 2] = ["etc/os-release", "usr/lib/os-release"];
+
+/// Fields of `os-release(5)`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OsRelease {
+    fields: HashMap<String, String>,
+}
+
+impl OsRelease {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.get(key).map(|value| value.as_str())
+    }
+}
+
+/// Undo shell-style quoting: `"..."` with `\` escapes, or `'...'`
+fn unquote(value: &str) -> String {
+    if let Some(inner) = value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+    {
+        return inner.to_string();
+    }
+
+    let inner = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value);
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => result.extend(chars.next()),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Parse `os-release` content
+///
+/// Comments, blank lines and malformed lines are skipped.
+pub fn parse_os_release(content: &str) -> OsRelease {
+    OsRelease {
+        fields: content
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .filter(|(key, _)| {
+                !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            })
+            .map(|(key, value)| (key.to_string(), unquote(value.trim())))
+            .collect(),
+    }
+}
+
+/// Read `<root>/etc/os-release`, falling back on `<root>/usr/lib/os-release`
+pub fn read_os_release(root: &Path) -> Option<OsRelease> {
+    OS_RELEASE_PATHS
+        .iter()
+        .find_map(|path| fs::read_to_string(root.join(path)).ok())
+        .map(|content| parse_os_release(&content))
+}
+
+fn read_kernel_attribute(root: &Path, name: &str) -> Option<String> {
+    fs::read_to_string(root.join("proc/sys/kernel").join(name))
+        .ok()
+        .map(|value| value.trim().to_string())
+}
+
+/// Kernel release, as `uname -r` prints it
+pub fn read_kernel_release(root: &Path) -> Option<String> {
+    read_kernel_attribute(root, "osrelease")
+}
+
+/// Kernel version, as `uname -v` prints it
+pub fn read_kernel_version(root: &Path) -> Option<String> {
+    read_kernel_attribute(root, "version")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OS_RELEASE: &str = r#"PRETTY_NAME="Ubuntu 22.04.4 LTS"
+NAME="Ubuntu"
+VERSION_ID="22.04"
+VERSION="22.04.4 LTS (Jammy Jellyfish)"
+VERSION_CODENAME=jammy
+ID=ubuntu
+ID_LIKE=debian
+# a comment
+HOME_URL="https://www.ubuntu.com/"
+VARIANT_ID='server'
+BUILD_ID="2024-02-\"16\""
+"#;
+
+    #[test]
+    fn test_parse_os_release() {
+        let os_release = parse_os_release(OS_RELEASE);
+
+        assert_eq!(os_release.get("ID"), Some("ubuntu"));
+        assert_eq!(os_release.get("ID_LIKE"), Some("debian"));
+        assert_eq!(os_release.get("VERSION_ID"), Some("22.04"));
+        assert_eq!(os_release.get("VERSION_CODENAME"), Some("jammy"));
+        assert_eq!(os_release.get("VARIANT_ID"), Some("server"));
+        assert_eq!(os_release.get("BUILD_ID"), Some("2024-02-\"16\""));
+        assert_eq!(os_release.get("PRETTY_NAME"), Some("Ubuntu 22.04.4 LTS"));
+        assert_eq!(os_release.get("# a comment"), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_read_os_release_fallback() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        assert_eq!(read_os_release(root.path()), None);
+
+        root.file("usr/lib/os-release", "ID=fedora\nVERSION_ID=40\n");
+        assert_eq!(
+            read_os_release(root.path()).unwrap().get("ID"),
+            Some("fedora")
+        );
+
+        root.file("etc/os-release", "ID=arch\n");
+        assert_eq!(
+            read_os_release(root.path()).unwrap().get("ID"),
+            Some("arch")
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_read_kernel_release_and_version() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        root.file("proc/sys/kernel/osrelease", "6.1.0-18-amd64\n")
+            .file(
+                "proc/sys/kernel/version",
+                "#1 SMP PREEMPT_DYNAMIC Debian 6.1.76-1 (2024-02-01)\n",
+            );
+
+        assert_eq!(
+            read_kernel_release(root.path()),
+            Some("6.1.0-18-amd64".to_string())
+        );
+        assert_eq!(
+            read_kernel_version(root.path()),
+            Some("#1 SMP PREEMPT_DYNAMIC Debian 6.1.76-1 (2024-02-01)".to_string())
+        );
+    }
+}
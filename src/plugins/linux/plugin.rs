@@ -0,0 +1,784 @@
+use std::{
+    cell::OnceCell,
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::core::error::DeviceInfoError;
+use crate::core::internal::{BaseDeviceInfoBuilder, DuplicatePolicy, IDeviceInfoBuilder};
+use crate::core::normalize::Normalizer;
+use crate::core::placeholder::PlaceholderPolicy;
+use crate::core::provenance::{Confidence, Provenance};
+use crate::core::smbios::SmbiosTables;
+use crate::core::string_tools::strip_trailing_newline;
+use crate::core::value::ComponentValue;
+use crate::plugins::linux::block::BlockDeviceResolver;
+use crate::plugins::linux::boot::{read_boot_id, read_boot_time, read_uptime};
+use crate::plugins::linux::cpu::{read_cpu_info, CpuInfo};
+use crate::plugins::linux::os_release::{
+    read_kernel_release, read_kernel_version, read_os_release, OsRelease,
+};
+use crate::plugins::linux::virt::detect_virtualization;
+
+const MACHINE_ID_PATHS: [&str; 2] = ["etc/machine-id", "var/lib/dbus/machine-id"];
+const DMI_ID_PATH: &str = "sys/class/dmi/id";
+
+#[allow(dead_code)]
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum LinuxBuilderComponents {
+    MachineId,
+    ProductUuid,
+    BoardSerialNumber,
+    ProductSerialNumber,
+    ChassisSerialNumber,
+    SystemSku,
+    OemStrings,
+    ProcessorSerialNumbers,
+    SystemDriveSerialNumber,
+    CpuVendor,
+    CpuModelName,
+    CpuFamily,
+    CpuModel,
+    CpuStepping,
+    CpuMicrocode,
+    CpuSockets,
+    CpuCores,
+    CpuThreads,
+    CpuFlagsHash,
+    Virtualization,
+    BootId,
+    BootTime,
+    Uptime,
+    OsId,
+    OsIdLike,
+    OsVersionId,
+    OsVersionCodename,
+    OsBuildId,
+    OsVariantId,
+    KernelRelease,
+    KernelVersion,
+}
+
+impl Serialize for LinuxBuilderComponents {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.as_string().serialize(serializer)
+    }
+}
+
+impl LinuxBuilderComponents {
+    pub fn as_string(&self) -> String {
+        match *self {
+            LinuxBuilderComponents::MachineId => "machineId".to_string(),
+            LinuxBuilderComponents::ProductUuid => "productUuid".to_string(),
+            LinuxBuilderComponents::BoardSerialNumber => "boardSerialNumber".to_string(),
+            LinuxBuilderComponents::ProductSerialNumber => "productSerialNumber".to_string(),
+            LinuxBuilderComponents::ChassisSerialNumber => "chassisSerialNumber".to_string(),
+            LinuxBuilderComponents::SystemSku => "systemSku".to_string(),
+            LinuxBuilderComponents::OemStrings => "oemStrings".to_string(),
+            LinuxBuilderComponents::ProcessorSerialNumbers => "processorSerialNumbers".to_string(),
+            LinuxBuilderComponents::SystemDriveSerialNumber => {
+                "systemDriveSerialNumber".to_string()
+            }
+            LinuxBuilderComponents::CpuVendor => "cpuVendor".to_string(),
+            LinuxBuilderComponents::CpuModelName => "cpuModelName".to_string(),
+            LinuxBuilderComponents::CpuFamily => "cpuFamily".to_string(),
+            LinuxBuilderComponents::CpuModel => "cpuModel".to_string(),
+            LinuxBuilderComponents::CpuStepping => "cpuStepping".to_string(),
+            LinuxBuilderComponents::CpuMicrocode => "cpuMicrocode".to_string(),
+            LinuxBuilderComponents::CpuSockets => "cpuSockets".to_string(),
+            LinuxBuilderComponents::CpuCores => "cpuCores".to_string(),
+            LinuxBuilderComponents::CpuThreads => "cpuThreads".to_string(),
+            LinuxBuilderComponents::CpuFlagsHash => "cpuFlagsHash".to_string(),
+            LinuxBuilderComponents::Virtualization => "virtualization".to_string(),
+            LinuxBuilderComponents::BootId => "bootId".to_string(),
+            LinuxBuilderComponents::BootTime => "bootTime".to_string(),
+            LinuxBuilderComponents::Uptime => "uptime".to_string(),
+            LinuxBuilderComponents::OsId => "osId".to_string(),
+            LinuxBuilderComponents::OsIdLike => "osIdLike".to_string(),
+            LinuxBuilderComponents::OsVersionId => "osVersionId".to_string(),
+            LinuxBuilderComponents::OsVersionCodename => "osVersionCodename".to_string(),
+            LinuxBuilderComponents::OsBuildId => "osBuildId".to_string(),
+            LinuxBuilderComponents::OsVariantId => "osVariantId".to_string(),
+            LinuxBuilderComponents::KernelRelease => "kernelRelease".to_string(),
+            LinuxBuilderComponents::KernelVersion => "kernelVersion".to_string(),
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "machineId" => Some(LinuxBuilderComponents::MachineId),
+            "productUuid" => Some(LinuxBuilderComponents::ProductUuid),
+            "boardSerialNumber" => Some(LinuxBuilderComponents::BoardSerialNumber),
+            "productSerialNumber" => Some(LinuxBuilderComponents::ProductSerialNumber),
+            "chassisSerialNumber" => Some(LinuxBuilderComponents::ChassisSerialNumber),
+            "systemSku" => Some(LinuxBuilderComponents::SystemSku),
+            "oemStrings" => Some(LinuxBuilderComponents::OemStrings),
+            "processorSerialNumbers" => Some(LinuxBuilderComponents::ProcessorSerialNumbers),
+            "systemDriveSerialNumber" => Some(LinuxBuilderComponents::SystemDriveSerialNumber),
+            "cpuVendor" => Some(LinuxBuilderComponents::CpuVendor),
+            "cpuModelName" => Some(LinuxBuilderComponents::CpuModelName),
+            "cpuFamily" => Some(LinuxBuilderComponents::CpuFamily),
+            "cpuModel" => Some(LinuxBuilderComponents::CpuModel),
+            "cpuStepping" => Some(LinuxBuilderComponents::CpuStepping),
+            "cpuMicrocode" => Some(LinuxBuilderComponents::CpuMicrocode),
+            "cpuSockets" => Some(LinuxBuilderComponents::CpuSockets),
+            "cpuCores" => Some(LinuxBuilderComponents::CpuCores),
+            "cpuThreads" => Some(LinuxBuilderComponents::CpuThreads),
+            "cpuFlagsHash" => Some(LinuxBuilderComponents::CpuFlagsHash),
+            "virtualization" => Some(LinuxBuilderComponents::Virtualization),
+            "bootId" => Some(LinuxBuilderComponents::BootId),
+            "bootTime" => Some(LinuxBuilderComponents::BootTime),
+            "uptime" => Some(LinuxBuilderComponents::Uptime),
+            "osId" => Some(LinuxBuilderComponents::OsId),
+            "osIdLike" => Some(LinuxBuilderComponents::OsIdLike),
+            "osVersionId" => Some(LinuxBuilderComponents::OsVersionId),
+            "osVersionCodename" => Some(LinuxBuilderComponents::OsVersionCodename),
+            "osBuildId" => Some(LinuxBuilderComponents::OsBuildId),
+            "osVariantId" => Some(LinuxBuilderComponents::OsVariantId),
+            "kernelRelease" => Some(LinuxBuilderComponents::KernelRelease),
+            "kernelVersion" => Some(LinuxBuilderComponents::KernelVersion),
+            _ => None,
+        }
+    }
+
+    /// Whether the value changes on reboot (or even between two reads)
+    ///
+    /// Volatile components identify a boot session rather than a device, so
+    /// they must only be used where that is intended, e.g. to pin a token to
+    /// the current session.
+    pub fn is_volatile(&self) -> bool {
+        matches!(
+            *self,
+            LinuxBuilderComponents::BootId
+                | LinuxBuilderComponents::BootTime
+                | LinuxBuilderComponents::Uptime
+        )
+    }
+
+    pub fn normalizer(&self) -> Normalizer {
+        match *self {
+            LinuxBuilderComponents::ProductUuid => Normalizer::SmbiosUuid,
+            LinuxBuilderComponents::BoardSerialNumber
+            | LinuxBuilderComponents::ProductSerialNumber
+            | LinuxBuilderComponents::ChassisSerialNumber
+            | LinuxBuilderComponents::ProcessorSerialNumbers
+            | LinuxBuilderComponents::SystemDriveSerialNumber => Normalizer::Serial,
+            _ => Normalizer::Trim,
+        }
+    }
+
+    /// The files read, below `sysroot` when it is not `/`
+    pub fn source(&self, sysroot: &Path) -> String {
+        let source = match *self {
+            LinuxBuilderComponents::MachineId => "/etc/machine-id, /var/lib/dbus/machine-id",
+            LinuxBuilderComponents::ProductUuid => "/sys/class/dmi/id/product_uuid",
+            LinuxBuilderComponents::BoardSerialNumber => "/sys/class/dmi/id/board_serial",
+            LinuxBuilderComponents::ProductSerialNumber => "/sys/class/dmi/id/product_serial",
+            LinuxBuilderComponents::ChassisSerialNumber => "/sys/class/dmi/id/chassis_serial",
+            LinuxBuilderComponents::SystemSku => "/sys/firmware/dmi/tables (SMBIOS type 1)",
+            LinuxBuilderComponents::OemStrings => "/sys/firmware/dmi/tables (SMBIOS type 11)",
+            LinuxBuilderComponents::ProcessorSerialNumbers => {
+                "/sys/firmware/dmi/tables (SMBIOS type 4)"
+            }
+            LinuxBuilderComponents::SystemDriveSerialNumber => {
+                "/proc/self/mountinfo, /sys/class/block, /run/udev/data"
+            }
+            LinuxBuilderComponents::CpuVendor
+            | LinuxBuilderComponents::CpuModelName
+            | LinuxBuilderComponents::CpuFamily
+            | LinuxBuilderComponents::CpuModel
+            | LinuxBuilderComponents::CpuStepping
+            | LinuxBuilderComponents::CpuMicrocode
+            | LinuxBuilderComponents::CpuFlagsHash => "/proc/cpuinfo",
+            LinuxBuilderComponents::CpuSockets
+            | LinuxBuilderComponents::CpuCores
+            | LinuxBuilderComponents::CpuThreads => {
+                "/sys/devices/system/cpu/cpu*/topology, /proc/cpuinfo"
+            }
+            LinuxBuilderComponents::Virtualization => {
+                "/.dockerenv, /run/.containerenv, /proc, /sys/class/dmi/id, /proc/cpuinfo"
+            }
+            LinuxBuilderComponents::BootId => "/proc/sys/kernel/random/boot_id",
+            LinuxBuilderComponents::BootTime => "/proc/stat",
+            LinuxBuilderComponents::Uptime => "/proc/uptime",
+            LinuxBuilderComponents::OsId
+            | LinuxBuilderComponents::OsIdLike
+            | LinuxBuilderComponents::OsVersionId
+            | LinuxBuilderComponents::OsVersionCodename
+            | LinuxBuilderComponents::OsBuildId
+            | LinuxBuilderComponents::OsVariantId => "/etc/os-release, /usr/lib/os-release",
+            LinuxBuilderComponents::KernelRelease => "/proc/sys/kernel/osrelease",
+            LinuxBuilderComponents::KernelVersion => "/proc/sys/kernel/version",
+        };
+        match sysroot == Path::new("/") {
+            true => source.to_string(),
+            false => format!("{} below {}", source, sysroot.to_string_lossy()),
+        }
+    }
+
+    /// The disk and the CPU topology are resolved through several files,
+    /// and virtualization is guessed
+    pub fn confidence(&self) -> Confidence {
+        match *self {
+            LinuxBuilderComponents::SystemDriveSerialNumber
+            | LinuxBuilderComponents::CpuSockets
+            | LinuxBuilderComponents::CpuCores
+            | LinuxBuilderComponents::CpuThreads
+            | LinuxBuilderComponents::Virtualization => Confidence::Medium,
+            _ => Confidence::High,
+        }
+    }
+}
+impl fmt::Display for LinuxBuilderComponents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_string().as_str())
+    }
+}
+
+pub trait ILinuxBuilder: IDeviceInfoBuilder<LinuxBuilderComponents> {
+    fn add_machine_id(&mut self) -> &mut Self;
+    fn add_product_uuid(&mut self) -> &mut Self;
+    fn add_board_serial_number(&mut self) -> &mut Self;
+    fn add_product_serial_number(&mut self) -> &mut Self;
+    fn add_chassis_serial_number(&mut self) -> &mut Self;
+    fn add_system_sku(&mut self) -> &mut Self;
+    fn add_oem_strings(&mut self) -> &mut Self;
+    fn add_processor_serial_numbers(&mut self) -> &mut Self;
+    fn add_system_drive_serial_number(&mut self) -> &mut Self;
+    fn add_cpu_vendor(&mut self) -> &mut Self;
+    fn add_cpu_model_name(&mut self) -> &mut Self;
+    fn add_cpu_family(&mut self) -> &mut Self;
+    fn add_cpu_model(&mut self) -> &mut Self;
+    fn add_cpu_stepping(&mut self) -> &mut Self;
+    fn add_cpu_microcode(&mut self) -> &mut Self;
+    fn add_cpu_sockets(&mut self) -> &mut Self;
+    fn add_cpu_cores(&mut self) -> &mut Self;
+    fn add_cpu_threads(&mut self) -> &mut Self;
+    fn add_cpu_flags_hash(&mut self) -> &mut Self;
+    fn add_virtualization(&mut self) -> &mut Self;
+    fn add_boot_id(&mut self) -> &mut Self;
+    fn add_boot_time(&mut self) -> &mut Self;
+    fn add_uptime(&mut self) -> &mut Self;
+    fn add_os_id(&mut self) -> &mut Self;
+    fn add_os_id_like(&mut self) -> &mut Self;
+    fn add_os_version_id(&mut self) -> &mut Self;
+    fn add_os_version_codename(&mut self) -> &mut Self;
+    fn add_os_build_id(&mut self) -> &mut Self;
+    fn add_os_variant_id(&mut self) -> &mut Self;
+    fn add_kernel_release(&mut self) -> &mut Self;
+    fn add_kernel_version(&mut self) -> &mut Self;
+}
+
+pub struct LinuxBuilder {
+    _base: BaseDeviceInfoBuilder<LinuxBuilderComponents>,
+    sysroot: PathBuf,
+    cpu_info: OnceCell<Option<CpuInfo>>,
+    os_release: OnceCell<Option<OsRelease>>,
+    smbios: OnceCell<Result<SmbiosTables, DeviceInfoError>>,
+}
+
+impl LinuxBuilder {
+    pub fn new() -> Self {
+        Self::with_sysroot(Path::new("/"))
+    }
+
+    /// Read `/proc`, `/sys`, `/etc`, ... below `sysroot` instead of `/`
+    pub fn with_sysroot(sysroot: &Path) -> Self {
+        Self {
+            _base: BaseDeviceInfoBuilder::<LinuxBuilderComponents>::new(),
+            sysroot: sysroot.to_path_buf(),
+            cpu_info: OnceCell::new(),
+            os_release: OnceCell::new(),
+            smbios: OnceCell::new(),
+        }
+    }
+
+    pub fn get_sysroot(&self) -> &Path {
+        &self.sysroot
+    }
+
+    fn not_found(&self, relative: &str) -> DeviceInfoError {
+        DeviceInfoError::NotFound(self.sysroot.join(relative).to_string_lossy().to_string())
+    }
+
+    /// `/proc/cpuinfo` is parsed once and shared by all CPU components
+    fn cpu_info(&self) -> Result<&CpuInfo, DeviceInfoError> {
+        self.cpu_info
+            .get_or_init(|| read_cpu_info(&self.sysroot))
+            .as_ref()
+            .ok_or_else(|| self.not_found("proc/cpuinfo"))
+    }
+
+    fn os_release_field(&self, key: &str) -> Result<String, DeviceInfoError> {
+        self.os_release
+            .get_or_init(|| read_os_release(&self.sysroot))
+            .as_ref()
+            .ok_or_else(|| self.not_found("etc/os-release"))?
+            .get(key)
+            .map(|value| value.to_string())
+            .ok_or_else(|| DeviceInfoError::NotFound(format!("os-release field {}", key)))
+    }
+
+    /// The SMBIOS tables are decoded once and shared by all SMBIOS components
+    ///
+    /// Like most DMI serials, `/sys/firmware/dmi/tables` is root-only.
+    fn smbios(&self) -> Result<&SmbiosTables, DeviceInfoError> {
+        self.smbios
+            .get_or_init(|| SmbiosTables::from_sysfs(&self.sysroot))
+            .as_ref()
+            .map_err(|e| e.clone())
+    }
+
+    fn read_dmi_attribute(&self, name: &str) -> Result<String, DeviceInfoError> {
+        read_attribute(&self.sysroot.join(DMI_ID_PATH).join(name))
+    }
+
+    fn read_machine_id(&self) -> Result<String, DeviceInfoError> {
+        MACHINE_ID_PATHS
+            .iter()
+            .filter_map(|path| read_attribute(&self.sysroot.join(path)).ok())
+            .find(|id| !id.is_empty())
+            .ok_or_else(|| self.not_found(MACHINE_ID_PATHS[0]))
+    }
+}
+
+impl IDeviceInfoBuilder<LinuxBuilderComponents> for LinuxBuilder {
+    fn get_components(&self) -> &HashMap<LinuxBuilderComponents, ComponentValue> {
+        &self._base.components
+    }
+    fn get_components_mut(&mut self) -> &mut HashMap<LinuxBuilderComponents, ComponentValue> {
+        &mut self._base.components
+    }
+    fn get_failures(&self) -> &HashMap<LinuxBuilderComponents, DeviceInfoError> {
+        &self._base.failures
+    }
+    fn get_failures_mut(&mut self) -> &mut HashMap<LinuxBuilderComponents, DeviceInfoError> {
+        &mut self._base.failures
+    }
+    fn get_provenance(&self) -> &HashMap<LinuxBuilderComponents, Provenance> {
+        &self._base.provenance
+    }
+    fn get_provenance_mut(&mut self) -> &mut HashMap<LinuxBuilderComponents, Provenance> {
+        &mut self._base.provenance
+    }
+    fn get_duplicate_policy(&self) -> DuplicatePolicy {
+        self._base.duplicate_policy
+    }
+    fn set_duplicate_policy(&mut self, policy: DuplicatePolicy) -> &mut Self {
+        self._base.duplicate_policy = policy;
+        self
+    }
+
+    fn get_placeholder_policy(&self) -> PlaceholderPolicy {
+        self._base.placeholder_policy
+    }
+
+    fn set_placeholder_policy(&mut self, policy: PlaceholderPolicy) -> &mut Self {
+        self._base.placeholder_policy = policy;
+        self
+    }
+
+    fn normalize(&self, name: &LinuxBuilderComponents, value: ComponentValue) -> ComponentValue {
+        name.normalizer().apply(value)
+    }
+
+    fn is_identifier(&self, name: &LinuxBuilderComponents) -> bool {
+        name.normalizer().is_identifier()
+    }
+
+    fn source(&self, name: &LinuxBuilderComponents) -> String {
+        name.source(&self.sysroot)
+    }
+
+    fn confidence(&self, name: &LinuxBuilderComponents) -> Confidence {
+        name.confidence()
+    }
+
+    fn probe(&self, name: &LinuxBuilderComponents) -> Result<ComponentValue, DeviceInfoError> {
+        let value = match *name {
+            LinuxBuilderComponents::MachineId => self.read_machine_id(),
+            LinuxBuilderComponents::ProductUuid => self.read_dmi_attribute("product_uuid"),
+            LinuxBuilderComponents::BoardSerialNumber => self.read_dmi_attribute("board_serial"),
+            LinuxBuilderComponents::ProductSerialNumber => {
+                self.read_dmi_attribute("product_serial")
+            }
+            LinuxBuilderComponents::ChassisSerialNumber => {
+                self.read_dmi_attribute("chassis_serial")
+            }
+            LinuxBuilderComponents::SystemSku => Ok(self
+                .smbios()?
+                .system()
+                .map(|system| system.sku_number.to_owned())
+                .unwrap_or_default()),
+            LinuxBuilderComponents::OemStrings => {
+                return Ok(ComponentValue::list(self.smbios()?.oem_strings()));
+            }
+            LinuxBuilderComponents::ProcessorSerialNumbers => {
+                return Ok(ComponentValue::list(
+                    self.smbios()?
+                        .processors()
+                        .iter()
+                        .map(|processor| processor.serial_number.as_str())
+                        .filter(|serial| !serial.is_empty()),
+                ));
+            }
+            LinuxBuilderComponents::SystemDriveSerialNumber => {
+                BlockDeviceResolver::new(&self.sysroot)
+                    .system_drive_serial_number()
+                    .ok_or_else(|| {
+                        DeviceInfoError::NotFound("Serial number of the root disk".to_string())
+                    })
+            }
+            LinuxBuilderComponents::CpuVendor => Ok(self.cpu_info()?.vendor.to_owned()),
+            LinuxBuilderComponents::CpuModelName => Ok(self.cpu_info()?.model_name.to_owned()),
+            LinuxBuilderComponents::CpuFamily => Ok(self.cpu_info()?.family.to_owned()),
+            LinuxBuilderComponents::CpuModel => Ok(self.cpu_info()?.model.to_owned()),
+            LinuxBuilderComponents::CpuStepping => Ok(self.cpu_info()?.stepping.to_owned()),
+            LinuxBuilderComponents::CpuMicrocode => Ok(self.cpu_info()?.microcode.to_owned()),
+            LinuxBuilderComponents::CpuSockets => Ok(self.cpu_info()?.sockets.to_string()),
+            LinuxBuilderComponents::CpuCores => Ok(self.cpu_info()?.cores.to_string()),
+            LinuxBuilderComponents::CpuThreads => Ok(self.cpu_info()?.threads.to_string()),
+            LinuxBuilderComponents::CpuFlagsHash => Ok(self.cpu_info()?.flags_hash()),
+            LinuxBuilderComponents::Virtualization => {
+                Ok(detect_virtualization(&self.sysroot).to_string())
+            }
+            LinuxBuilderComponents::BootId => read_boot_id(&self.sysroot)
+                .ok_or_else(|| self.not_found("proc/sys/kernel/random/boot_id")),
+            LinuxBuilderComponents::BootTime => read_boot_time(&self.sysroot)
+                .map(|time| time.to_string())
+                .ok_or_else(|| self.not_found("proc/stat")),
+            LinuxBuilderComponents::Uptime => read_uptime(&self.sysroot)
+                .map(|uptime| uptime.to_string())
+                .ok_or_else(|| self.not_found("proc/uptime")),
+            LinuxBuilderComponents::OsId => self.os_release_field("ID"),
+            LinuxBuilderComponents::OsIdLike => self.os_release_field("ID_LIKE"),
+            LinuxBuilderComponents::OsVersionId => self.os_release_field("VERSION_ID"),
+            LinuxBuilderComponents::OsVersionCodename => self.os_release_field("VERSION_CODENAME"),
+            LinuxBuilderComponents::OsBuildId => self.os_release_field("BUILD_ID"),
+            LinuxBuilderComponents::OsVariantId => self.os_release_field("VARIANT_ID"),
+            LinuxBuilderComponents::KernelRelease => read_kernel_release(&self.sysroot)
+                .ok_or_else(|| self.not_found("proc/sys/kernel/osrelease")),
+            LinuxBuilderComponents::KernelVersion => read_kernel_version(&self.sysroot)
+                .ok_or_else(|| self.not_found("proc/sys/kernel/version")),
+        };
+        value.map(ComponentValue::from)
+    }
+}
+
+/// Read a single-line attribute file, e.g. from `/etc` or sysfs
+///
+/// Most DMI serials are root-only, which surfaces as
+/// [`DeviceInfoError::PermissionDenied`].
+fn read_attribute(path: &Path) -> Result<String, DeviceInfoError> {
+    fs::read_to_string(path)
+        .map(|content| strip_trailing_newline(&content).trim().to_string())
+        .map_err(|e| DeviceInfoError::from_io(&path.to_string_lossy(), e))
+}
+
+impl ILinuxBuilder for LinuxBuilder {
+    fn add_machine_id(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::MachineId)
+    }
+
+    fn add_product_uuid(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::ProductUuid)
+    }
+
+    fn add_board_serial_number(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::BoardSerialNumber)
+    }
+
+    fn add_product_serial_number(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::ProductSerialNumber)
+    }
+
+    fn add_chassis_serial_number(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::ChassisSerialNumber)
+    }
+
+    fn add_system_sku(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::SystemSku)
+    }
+
+    fn add_oem_strings(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::OemStrings)
+    }
+
+    fn add_processor_serial_numbers(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::ProcessorSerialNumbers)
+    }
+
+    fn add_system_drive_serial_number(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::SystemDriveSerialNumber)
+    }
+
+    fn add_cpu_vendor(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::CpuVendor)
+    }
+
+    fn add_cpu_model_name(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::CpuModelName)
+    }
+
+    fn add_cpu_family(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::CpuFamily)
+    }
+
+    fn add_cpu_model(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::CpuModel)
+    }
+
+    fn add_cpu_stepping(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::CpuStepping)
+    }
+
+    fn add_cpu_microcode(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::CpuMicrocode)
+    }
+
+    fn add_cpu_sockets(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::CpuSockets)
+    }
+
+    fn add_cpu_cores(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::CpuCores)
+    }
+
+    fn add_cpu_threads(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::CpuThreads)
+    }
+
+    fn add_cpu_flags_hash(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::CpuFlagsHash)
+    }
+
+    fn add_virtualization(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::Virtualization)
+    }
+
+    fn add_boot_id(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::BootId)
+    }
+
+    fn add_boot_time(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::BootTime)
+    }
+
+    fn add_uptime(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::Uptime)
+    }
+
+    fn add_os_id(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::OsId)
+    }
+
+    fn add_os_id_like(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::OsIdLike)
+    }
+
+    fn add_os_version_id(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::OsVersionId)
+    }
+
+    fn add_os_version_codename(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::OsVersionCodename)
+    }
+
+    fn add_os_build_id(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::OsBuildId)
+    }
+
+    fn add_os_variant_id(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::OsVariantId)
+    }
+
+    fn add_kernel_release(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::KernelRelease)
+    }
+
+    fn add_kernel_version(&mut self) -> &mut Self {
+        self.add_probed(&LinuxBuilderComponents::KernelVersion)
+    }
+}
+
+impl Default for LinuxBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linux_components_round_trip() {
+        for component in [
+            LinuxBuilderComponents::MachineId,
+            LinuxBuilderComponents::ProductUuid,
+            LinuxBuilderComponents::BoardSerialNumber,
+            LinuxBuilderComponents::ProductSerialNumber,
+            LinuxBuilderComponents::ChassisSerialNumber,
+            LinuxBuilderComponents::SystemSku,
+            LinuxBuilderComponents::OemStrings,
+            LinuxBuilderComponents::ProcessorSerialNumbers,
+            LinuxBuilderComponents::SystemDriveSerialNumber,
+            LinuxBuilderComponents::CpuVendor,
+            LinuxBuilderComponents::CpuModelName,
+            LinuxBuilderComponents::CpuFamily,
+            LinuxBuilderComponents::CpuModel,
+            LinuxBuilderComponents::CpuStepping,
+            LinuxBuilderComponents::CpuMicrocode,
+            LinuxBuilderComponents::CpuSockets,
+            LinuxBuilderComponents::CpuCores,
+            LinuxBuilderComponents::CpuThreads,
+            LinuxBuilderComponents::CpuFlagsHash,
+            LinuxBuilderComponents::Virtualization,
+            LinuxBuilderComponents::BootId,
+            LinuxBuilderComponents::BootTime,
+            LinuxBuilderComponents::Uptime,
+            LinuxBuilderComponents::OsId,
+            LinuxBuilderComponents::OsIdLike,
+            LinuxBuilderComponents::OsVersionId,
+            LinuxBuilderComponents::OsVersionCodename,
+            LinuxBuilderComponents::OsBuildId,
+            LinuxBuilderComponents::OsVariantId,
+            LinuxBuilderComponents::KernelRelease,
+            LinuxBuilderComponents::KernelVersion,
+        ] {
+            assert_eq!(
+                LinuxBuilderComponents::from_str(&component.as_string()),
+                Some(component)
+            );
+        }
+    }
+
+    #[test]
+    fn test_linux_components_volatile() {
+        assert!(LinuxBuilderComponents::BootId.is_volatile());
+        assert!(LinuxBuilderComponents::Uptime.is_volatile());
+        assert!(!LinuxBuilderComponents::MachineId.is_volatile());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_linux_builder() {
+        let mut builder = LinuxBuilder::new();
+        builder
+            .add_machine_id()
+            .add_product_uuid()
+            .add_board_serial_number()
+            .add_product_serial_number()
+            .add_chassis_serial_number()
+            .add_system_drive_serial_number()
+            .add_cpu_vendor()
+            .add_cpu_model_name()
+            .add_cpu_threads()
+            .add_cpu_flags_hash()
+            .add_virtualization()
+            .add_boot_id()
+            .add_boot_time()
+            .add_uptime()
+            .add_os_id()
+            .add_os_version_id()
+            .add_kernel_release();
+
+        let components = builder.get_components();
+        assert!(components.get(&LinuxBuilderComponents::MachineId).is_some());
+        println!("{:?}", components);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_linux_builder_sysroot() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        root.file(
+            "var/lib/dbus/machine-id",
+            "3d1219c7c4c5404aaa1f6d2a48adfda4\n",
+        )
+        .file("sys/class/dmi/id/board_serial", "  BSN12345  \n")
+        .file("proc/cpuinfo", include_str!("fixtures/cpuinfo_x86_64.txt"))
+        .file("usr/lib/os-release", "ID=debian\nVERSION_ID=\"12\"\n");
+
+        let mut builder = LinuxBuilder::with_sysroot(root.path());
+        builder
+            .add_machine_id()
+            .add_board_serial_number()
+            .add_product_uuid()
+            .add_cpu_threads()
+            .add_os_id()
+            .add_os_version_id()
+            .add_os_id_like();
+
+        let components = builder.get_components();
+        assert_eq!(
+            components[&LinuxBuilderComponents::MachineId],
+            "3d1219c7c4c5404aaa1f6d2a48adfda4"
+        );
+        assert_eq!(
+            components[&LinuxBuilderComponents::BoardSerialNumber],
+            "BSN12345"
+        );
+        assert_eq!(components[&LinuxBuilderComponents::ProductUuid], "");
+        assert_eq!(components[&LinuxBuilderComponents::CpuThreads], "4");
+        assert_eq!(components[&LinuxBuilderComponents::OsId], "debian");
+        assert_eq!(components[&LinuxBuilderComponents::OsVersionId], "12");
+        assert_eq!(components[&LinuxBuilderComponents::OsIdLike], "");
+
+        assert!(matches!(
+            builder.probe(&LinuxBuilderComponents::ProductUuid),
+            Err(DeviceInfoError::NotFound(_))
+        ));
+        assert!(matches!(
+            builder.probe(&LinuxBuilderComponents::OsIdLike),
+            Err(DeviceInfoError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_linux_builder_smbios() {
+        let root = crate::plugins::linux::fake_root::FakeRoot::new();
+        let mut builder = LinuxBuilder::with_sysroot(root.path());
+        builder.add_system_sku();
+        assert_eq!(
+            builder.get_components()[&LinuxBuilderComponents::SystemSku],
+            ""
+        );
+
+        root.file(
+            "sys/firmware/dmi/tables/smbios_entry_point",
+            include_bytes!("../../core/fixtures/smbios3_entry_point.bin"),
+        )
+        .file(
+            "sys/firmware/dmi/tables/DMI",
+            include_bytes!("../../core/fixtures/smbios3_dmi.bin"),
+        );
+        let mut builder = LinuxBuilder::with_sysroot(root.path());
+        builder
+            .add_system_sku()
+            .add_oem_strings()
+            .add_processor_serial_numbers();
+
+        let components = builder.get_components();
+        assert_eq!(
+            components[&LinuxBuilderComponents::SystemSku],
+            "SKU=0859;ModelName=OptiPlex 7060"
+        );
+        assert_eq!(
+            components[&LinuxBuilderComponents::OemStrings],
+            ComponentValue::list(["1[0859]", "APPLIANCE:acme-edge-01", "Dell System"])
+        );
+        assert_eq!(
+            components[&LinuxBuilderComponents::ProcessorSerialNumbers],
+            ComponentValue::list(["PSN-0002", "PSN-0001"])
+        );
+    }
+}
@@ -0,0 +1,218 @@
+//! Virtualization and container detection, along the lines of
+//! `systemd-detect-virt`
+//!
+//! Containers are checked first, since a container running inside a VM
+//! still sees the DMI tables of the VM.
+
+use std::{fmt, fs, path::Path};
+
+use crate::plugins::linux::cpu::parse_cpuinfo;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum VirtualizationKind {
+    None,
+    Container,
+    VirtualMachine,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Virtualization {
+    pub kind: VirtualizationKind,
+    /// Technology name as reported by `systemd-detect-virt` (`docker`,
+    /// `wsl`, `kvm`, ...), `none` on bare metal
+    pub name: String,
+}
+
+impl Virtualization {
+    fn new(kind: VirtualizationKind, name: &str) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+        }
+    }
+
+    pub fn is_virtualized(&self) -> bool {
+        self.kind != VirtualizationKind::None
+    }
+}
+
+impl fmt::Display for Virtualization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name.as_str())
+    }
+}
+
+/// `/proc/1/cgroup` path fragments of container runtimes
+const CGROUP_CONTAINERS: [(&str, &str); 5] = [
+    ("/docker", "docker"),
+    ("/libpod", "podman"),
+    ("/kubepods", "container-other"),
+    ("/lxc", "lxc"),
+    ("/containerd", "container-other"),
+];
+
+/// DMI `sys_vendor` / `product_name` prefixes of hypervisors
+const DMI_VENDORS: [(&str, &str); 11] = [
+    ("KVM", "kvm"),
+    ("OpenStack", "kvm"),
+    ("QEMU", "qemu"),
+    ("VMware", "vmware"),
+    ("VMW", "vmware"),
+    ("innotek GmbH", "oracle"),
+    ("VirtualBox", "oracle"),
+    ("Xen", "xen"),
+    ("Bochs", "bochs"),
+    ("Parallels", "parallels"),
+    ("Amazon EC2", "amazon"),
+];
+
+fn read(root: &Path, relative: &str) -> Option<String> {
+    fs::read_to_string(root.join(relative)).ok()
+}
+
+fn detect_container(root: &Path) -> Option<Virtualization> {
+    if root.join(".dockerenv").exists() {
+        return Some(Virtualization::new(VirtualizationKind::Container, "docker"));
+    }
+    if root.join("run/.containerenv").exists() {
+        return Some(Virtualization::new(VirtualizationKind::Container, "podman"));
+    }
+
+    let cgroup = read(root, "proc/1/cgroup").unwrap_or_default();
+    let by_cgroup = CGROUP_CONTAINERS
+        .iter()
+        .find(|(fragment, _)| cgroup.contains(fragment))
+        .map(|(_, name)| Virtualization::new(VirtualizationKind::Container, name));
+    if by_cgroup.is_some() {
+        return by_cgroup;
+    }
+
+    let osrelease = read(root, "proc/sys/kernel/osrelease")
+        .unwrap_or_default()
+        .to_lowercase();
+    if osrelease.contains("microsoft") || osrelease.contains("wsl") {
+        return Some(Virtualization::new(VirtualizationKind::Container, "wsl"));
+    }
+
+    None
+}
+
+fn detect_vm(root: &Path) -> Option<Virtualization> {
+    let dmi = ["sys_vendor", "product_name"]
+        .iter()
+        .filter_map(|name| read(root, &format!("sys/class/dmi/id/{}", name)))
+        .map(|value| value.trim().to_string())
+        .collect::<Vec<String>>();
+
+    let by_vendor = DMI_VENDORS
+        .iter()
+        .find(|(vendor, _)| dmi.iter().any(|value| value.starts_with(vendor)))
+        .map(|(_, name)| Virtualization::new(VirtualizationKind::VirtualMachine, name));
+    if by_vendor.is_some() {
+        return by_vendor;
+    }
+
+    let (vendor, product) = match dmi.as_slice() {
+        [vendor, product] => (vendor.as_str(), product.as_str()),
+        _ => ("", ""),
+    };
+    if vendor == "Microsoft Corporation" && product == "Virtual Machine" {
+        return Some(Virtualization::new(
+            VirtualizationKind::VirtualMachine,
+            "microsoft",
+        ));
+    }
+    if vendor == "Google" {
+        return Some(Virtualization::new(
+            VirtualizationKind::VirtualMachine,
+            "google",
+        ));
+    }
+
+    read(root, "proc/cpuinfo")
+        .map(|content| parse_cpuinfo(&content))
+        .filter(|cpu_info| cpu_info.flags.contains("hypervisor"))
+        .map(|_| Virtualization::new(VirtualizationKind::VirtualMachine, "vm-other"))
+}
+
+/// Detect the container runtime or hypervisor the system under `root` runs in
+pub fn detect_virtualization(root: &Path) -> Virtualization {
+    detect_container(root)
+        .or_else(|| detect_vm(root))
+        .unwrap_or_else(|| Virtualization::new(VirtualizationKind::None, "none"))
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use super::*;
+    use crate::plugins::linux::fake_root::FakeRoot;
+
+    const CPUINFO_X86_64: &str = include_str!("fixtures/cpuinfo_x86_64.txt");
+
+    fn bare_metal() -> FakeRoot {
+        let root = FakeRoot::new();
+        root.file("proc/1/cgroup", "0::/init.scope\n")
+            .file("proc/sys/kernel/osrelease", "6.1.0-18-amd64\n")
+            .file("proc/cpuinfo", CPUINFO_X86_64)
+            .file("sys/class/dmi/id/sys_vendor", "Dell Inc.\n")
+            .file("sys/class/dmi/id/product_name", "OptiPlex 7060\n");
+        root
+    }
+
+    #[test]
+    fn test_bare_metal() {
+        let root = bare_metal();
+        let virtualization = detect_virtualization(root.path());
+
+        assert!(!virtualization.is_virtualized());
+        assert_eq!(virtualization.to_string(), "none");
+    }
+
+    #[test]
+    fn test_containers() {
+        let root = bare_metal();
+        root.file(
+            "proc/1/cgroup",
+            "0::/system.slice/docker-3f2a.scope\n12:pids:/docker/3f2a\n",
+        );
+        assert_eq!(detect_virtualization(root.path()).name, "docker");
+
+        let root = bare_metal();
+        root.file("run/.containerenv", "");
+        assert_eq!(detect_virtualization(root.path()).name, "podman");
+
+        let root = bare_metal();
+        root.file(
+            "proc/sys/kernel/osrelease",
+            "5.15.133.1-microsoft-standard-WSL2\n",
+        );
+        let virtualization = detect_virtualization(root.path());
+        assert_eq!(virtualization.kind, VirtualizationKind::Container);
+        assert_eq!(virtualization.name, "wsl");
+    }
+
+    #[test]
+    fn test_virtual_machines() {
+        let root = bare_metal();
+        root.file("sys/class/dmi/id/sys_vendor", "QEMU\n").file(
+            "sys/class/dmi/id/product_name",
+            "Standard PC (Q35 + ICH9, 2009)\n",
+        );
+        assert_eq!(detect_virtualization(root.path()).name, "qemu");
+
+        let root = bare_metal();
+        root.file("sys/class/dmi/id/sys_vendor", "Microsoft Corporation\n")
+            .file("sys/class/dmi/id/product_name", "Virtual Machine\n");
+        assert_eq!(detect_virtualization(root.path()).name, "microsoft");
+
+        let root = bare_metal();
+        root.file(
+            "proc/cpuinfo",
+            CPUINFO_X86_64.replace("flags\t\t: fpu", "flags\t\t: hypervisor fpu"),
+        );
+        let virtualization = detect_virtualization(root.path());
+        assert_eq!(virtualization.kind, VirtualizationKind::VirtualMachine);
+        assert_eq!(virtualization.name, "vm-other");
+    }
+}
@@ -0,0 +1,11 @@
+use std::process::Command;
+
+/// Total memory in bytes, from `sysctl -n hw.memsize`
+pub fn total_memory() -> Option<u64> {
+    Command::new("sysctl")
+        .args(["-n", "hw.memsize"])
+        .output()
+        .ok()
+        .and_then(|cmd| String::from_utf8(cmd.stdout).ok())
+        .and_then(|output| output.trim().parse().ok())
+}
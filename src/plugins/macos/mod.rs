@@ -1 +1,4 @@
+#[cfg(target_os = "macos")]
+pub mod memory;
+pub mod network;
 pub mod plugin;
@@ -0,0 +1,98 @@
+#[cfg(target_os = "macos")]
+use std::process::Command;
+
+use crate::core::network::{NetworkAdapter, NetworkAdapterKind};
+
+fn adapter_kind(port: &str, device: &str) -> NetworkAdapterKind {
+    let port = port.to_lowercase();
+    if port.contains("wi-fi") || port.contains("airport") {
+        NetworkAdapterKind::Wireless
+    } else if port.contains("bridge")
+        || port.contains("bluetooth")
+        || port.contains("vpn")
+        || port.contains("iphone")
+        || !device.starts_with("en")
+    {
+        NetworkAdapterKind::Virtual
+    } else {
+        NetworkAdapterKind::Physical
+    }
+}
+
+/// Parse the output of `networksetup -listallhardwareports`
+pub fn parse_hardware_ports(output: &str) -> Vec<NetworkAdapter> {
+    output
+        .split("\n\n")
+        .filter_map(|block| {
+            let field = |name: &str| {
+                block
+                    .lines()
+                    .find_map(|line| line.strip_prefix(name))
+                    .map(|value| value.trim().to_string())
+            };
+            let port = field("Hardware Port:")?;
+            let device = field("Device:")?;
+            let mac_address = field("Ethernet Address:").filter(|address| address != "N/A")?;
+
+            Some(NetworkAdapter {
+                kind: adapter_kind(&port, &device),
+                name: device,
+                mac_address,
+                driver: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+pub fn list_network_adapters() -> Vec<NetworkAdapter> {
+    Command::new("networksetup")
+        .arg("-listallhardwareports")
+        .output()
+        .ok()
+        .and_then(|cmd| String::from_utf8(cmd.stdout).ok())
+        .map(|output| parse_hardware_ports(&output))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hardware_ports() {
+        let output = "\n\
+            Hardware Port: Ethernet\n\
+            Device: en0\n\
+            Ethernet Address: 3c:22:fb:00:00:01\n\
+            \n\
+            Hardware Port: Wi-Fi\n\
+            Device: en1\n\
+            Ethernet Address: 3c:22:fb:00:00:02\n\
+            \n\
+            Hardware Port: Thunderbolt Bridge\n\
+            Device: bridge0\n\
+            Ethernet Address: 82:00:00:00:00:01\n\
+            \n\
+            Hardware Port: Bluetooth PAN\n\
+            Device: en5\n\
+            Ethernet Address: N/A\n\
+            \n\
+            VLAN Configurations\n\
+            ===================\n";
+
+        let adapters = parse_hardware_ports(output);
+        let kinds: Vec<(&str, NetworkAdapterKind)> = adapters
+            .iter()
+            .map(|adapter| (adapter.name.as_str(), adapter.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("en0", NetworkAdapterKind::Physical),
+                ("en1", NetworkAdapterKind::Wireless),
+                ("bridge0", NetworkAdapterKind::Virtual),
+            ]
+        );
+    }
+}
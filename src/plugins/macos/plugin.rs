@@ -4,9 +4,19 @@ use std::{collections::HashMap, fmt};
 
 use serde::Serialize;
 
-use crate::core::internal::{BaseDeviceInfoBuilder, IDeviceInfoBuilder};
+use crate::core::error::DeviceInfoError;
+use crate::core::internal::{BaseDeviceInfoBuilder, DuplicatePolicy, IDeviceInfoBuilder};
+use crate::core::normalize::Normalizer;
+use crate::core::placeholder::PlaceholderPolicy;
+use crate::core::provenance::{Confidence, Provenance};
 #[cfg(target_os = "macos")]
 use crate::core::string_tools::strip_trailing_newline;
+use crate::core::value::ComponentValue;
+
+const SYSTEM_DRIVE_SERIAL_COMMAND: &str =
+    "system_profiler SPNVMeDataType | sed -En 's/.*Serial Number: ([\\d\\w]*)//p'";
+const PLATFORM_SERIAL_COMMAND: &str =
+    "ioreg -l | grep IOPlatformSerialNumber | sed 's/.*= //' | sed 's/\"//g'";
 
 #[allow(dead_code)]
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
@@ -34,6 +44,7 @@ impl MacOSBuilderComponents {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "systemDriveSerialNumber" => Some(MacOSBuilderComponents::SystemDriveSerialNumber),
@@ -41,6 +52,30 @@ impl MacOSBuilderComponents {
             _ => None,
         }
     }
+
+    pub fn normalizer(&self) -> Normalizer {
+        Normalizer::Serial
+    }
+
+    /// The shell pipeline run
+    pub fn source(&self) -> String {
+        match *self {
+            MacOSBuilderComponents::SystemDriveSerialNumber => {
+                format!("sh -c {}", SYSTEM_DRIVE_SERIAL_COMMAND)
+            }
+            MacOSBuilderComponents::PlatformSerialNumber => {
+                format!("sh -c {}", PLATFORM_SERIAL_COMMAND)
+            }
+        }
+    }
+
+    /// The system drive is taken to be the first NVMe drive
+    pub fn confidence(&self) -> Confidence {
+        match *self {
+            MacOSBuilderComponents::SystemDriveSerialNumber => Confidence::Medium,
+            MacOSBuilderComponents::PlatformSerialNumber => Confidence::High,
+        }
+    }
 }
 impl fmt::Display for MacOSBuilderComponents {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -65,61 +100,107 @@ impl MacOSBuilder {
     }
 }
 
+/// Run a shell pipeline and return its trimmed standard output
+#[cfg(target_os = "macos")]
+fn run_shell(command: &str) -> Result<String, DeviceInfoError> {
+    let cmd = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .map_err(|e| DeviceInfoError::from_io(command, e))?;
+    if !cmd.status.success() {
+        return Err(DeviceInfoError::ProbeFailed(format!(
+            "{} exited with {}",
+            command, cmd.status
+        )));
+    }
+
+    let output = String::from_utf8(cmd.stdout)
+        .map_err(|e| DeviceInfoError::Parse(format!("{}: {}", command, e)))?;
+    Ok(strip_trailing_newline(&output).to_string())
+}
+
 impl IDeviceInfoBuilder<MacOSBuilderComponents> for MacOSBuilder {
-    fn get_components(&self) -> &HashMap<MacOSBuilderComponents, String> {
+    fn get_components(&self) -> &HashMap<MacOSBuilderComponents, ComponentValue> {
         &self._base.components
     }
-    fn get_components_mut(&mut self) -> &mut HashMap<MacOSBuilderComponents, String> {
+    fn get_components_mut(&mut self) -> &mut HashMap<MacOSBuilderComponents, ComponentValue> {
         &mut self._base.components
     }
-}
+    fn get_failures(&self) -> &HashMap<MacOSBuilderComponents, DeviceInfoError> {
+        &self._base.failures
+    }
+    fn get_failures_mut(&mut self) -> &mut HashMap<MacOSBuilderComponents, DeviceInfoError> {
+        &mut self._base.failures
+    }
+    fn get_provenance(&self) -> &HashMap<MacOSBuilderComponents, Provenance> {
+        &self._base.provenance
+    }
+    fn get_provenance_mut(&mut self) -> &mut HashMap<MacOSBuilderComponents, Provenance> {
+        &mut self._base.provenance
+    }
+    fn get_duplicate_policy(&self) -> DuplicatePolicy {
+        self._base.duplicate_policy
+    }
+    fn set_duplicate_policy(&mut self, policy: DuplicatePolicy) -> &mut Self {
+        self._base.duplicate_policy = policy;
+        self
+    }
 
-impl IMacOSBuilder for MacOSBuilder {
-    fn add_system_drive_serial_number(&mut self) -> &mut Self {
+    fn get_placeholder_policy(&self) -> PlaceholderPolicy {
+        self._base.placeholder_policy
+    }
+
+    fn set_placeholder_policy(&mut self, policy: PlaceholderPolicy) -> &mut Self {
+        self._base.placeholder_policy = policy;
+        self
+    }
+
+    fn normalize(&self, name: &MacOSBuilderComponents, value: ComponentValue) -> ComponentValue {
+        name.normalizer().apply(value)
+    }
+
+    fn is_identifier(&self, name: &MacOSBuilderComponents) -> bool {
+        name.normalizer().is_identifier()
+    }
+
+    fn source(&self, name: &MacOSBuilderComponents) -> String {
+        name.source()
+    }
+
+    fn confidence(&self, name: &MacOSBuilderComponents) -> Confidence {
+        name.confidence()
+    }
+
+    fn probe(&self, name: &MacOSBuilderComponents) -> Result<ComponentValue, DeviceInfoError> {
         #[cfg(target_os = "macos")]
         {
-            let cmd = Command::new("sh")
-                .arg("-c")
-                .arg("system_profiler SPNVMeDataType | sed -En 's/.*Serial Number: ([\\d\\w]*)//p'")
-                .output()
-                .expect("failed to execute process");
-
-            let output = String::from_utf8(cmd.stdout).expect("failed to decode output");
-
-            self.add_component(
-                &MacOSBuilderComponents::SystemDriveSerialNumber,
-                strip_trailing_newline(&output),
-            );
-            self
+            let value = match *name {
+                MacOSBuilderComponents::SystemDriveSerialNumber => {
+                    run_shell(SYSTEM_DRIVE_SERIAL_COMMAND)
+                }
+                MacOSBuilderComponents::PlatformSerialNumber => run_shell(PLATFORM_SERIAL_COMMAND),
+            };
+            value.map(ComponentValue::from)
         }
 
         #[cfg(not(target_os = "macos"))]
         {
-            todo!()
+            Err(DeviceInfoError::Unsupported(format!(
+                "MacOS::{} is only available on macOS",
+                name
+            )))
         }
     }
+}
+
+impl IMacOSBuilder for MacOSBuilder {
+    fn add_system_drive_serial_number(&mut self) -> &mut Self {
+        self.add_probed(&MacOSBuilderComponents::SystemDriveSerialNumber)
+    }
 
     fn add_platform_serial_number(&mut self) -> &mut Self {
-        #[cfg(target_os = "macos")]
-        {
-            let cmd = Command::new("sh")
-                .arg("-c")
-                .arg("ioreg -l | grep IOPlatformSerialNumber | sed 's/.*= //' | sed 's/\"//g'")
-                .output()
-                .expect("failed to execute process");
-
-            let output = String::from_utf8(cmd.stdout).expect("failed to decode output");
-
-            self.add_component(
-                &MacOSBuilderComponents::PlatformSerialNumber,
-                strip_trailing_newline(&output),
-            );
-            self
-        }
-        #[cfg(not(target_os = "macos"))]
-        {
-            todo!()
-        }
+        self.add_probed(&MacOSBuilderComponents::PlatformSerialNumber)
     }
 }
 
@@ -130,11 +211,42 @@ impl Default for MacOSBuilder {
 }
 
 #[cfg(test)]
-#[cfg(target_os = "macos")]
 mod tests {
     use super::*;
 
     #[test]
+    #[cfg(unix)]
+    fn test_system_drive_serial_command() {
+        use std::io::Write;
+        use std::process::Stdio;
+
+        let command =
+            SYSTEM_DRIVE_SERIAL_COMMAND.replacen("system_profiler SPNVMeDataType", "cat", 1);
+        let mut child = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(include_bytes!("fixtures/SPNVMeDataType.txt"))
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+
+        assert_eq!(
+            MacOSBuilderComponents::SystemDriveSerialNumber
+                .normalizer()
+                .apply_str(&String::from_utf8(output.stdout).unwrap()),
+            "0BA0179C20A14F27"
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "macos")]
     fn test_macos_builder() {
         let mut builder = MacOSBuilder::new();
         builder.add_system_drive_serial_number();
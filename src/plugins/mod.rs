@@ -1,2 +1,3 @@
+pub mod linux;
 pub mod macos;
 pub mod windows;
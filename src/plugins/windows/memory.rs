@@ -0,0 +1,44 @@
+use crate::core::error::DeviceInfoError;
+use crate::core::memory::MemoryModule;
+use crate::plugins::windows::plugin::{MEMORY_MODULES_QUERY, TOTAL_MEMORY_QUERY};
+use crate::plugins::windows::wmi::WmiSingleton;
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ComputerSystemQueryResult {
+    total_physical_memory: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PhysicalMemoryQueryResult {
+    device_locator: Option<String>,
+    /// `uint64` properties come back from WMI as strings
+    capacity: Option<String>,
+    manufacturer: Option<String>,
+    part_number: Option<String>,
+    serial_number: Option<String>,
+}
+
+pub fn total_memory() -> Option<u64> {
+    let system: ComputerSystemQueryResult = WmiSingleton::first(TOTAL_MEMORY_QUERY).ok()?;
+    system
+        .total_physical_memory
+        .and_then(|total| total.trim().parse().ok())
+}
+
+pub fn list_memory_modules() -> Result<Vec<MemoryModule>, DeviceInfoError> {
+    let res: Vec<PhysicalMemoryQueryResult> = WmiSingleton::raw_query(MEMORY_MODULES_QUERY)?;
+    let field = |value: &Option<String>| value.as_deref().unwrap_or("").trim().to_string();
+
+    Ok(res
+        .iter()
+        .map(|module| MemoryModule {
+            slot: field(&module.device_locator),
+            size_mb: field(&module.capacity).parse::<u64>().unwrap_or_default() / (1024 * 1024),
+            manufacturer: field(&module.manufacturer),
+            part_number: field(&module.part_number),
+            serial_number: field(&module.serial_number),
+        })
+        .collect())
+}
@@ -1,4 +1,8 @@
 pub mod plugin;
 
+#[cfg(target_os = "windows")]
+pub mod memory;
+#[cfg(target_os = "windows")]
+pub mod network;
 #[cfg(target_os = "windows")]
 pub mod wmi;
@@ -0,0 +1,63 @@
+use crate::core::network::{NetworkAdapter, NetworkAdapterKind};
+use crate::plugins::windows::plugin::NETWORK_ADAPTERS_QUERY;
+use crate::plugins::windows::wmi::WmiSingleton;
+
+/// `AdapterTypeId` of 802.11 adapters
+const ADAPTER_TYPE_WIRELESS: u16 = 9;
+
+/// Plug and Play enumerators of software adapters
+///
+/// `ROOT\` holds Hyper-V, VPN and TAP drivers, `SWD\` software devices and
+/// `BTH` Bluetooth PAN.
+const VIRTUAL_PNP_PREFIXES: [&str; 3] = ["ROOT\\", "SWD\\", "BTH"];
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct NetworkAdapterQueryResult {
+    name: String,
+    #[serde(rename = "MACAddress")]
+    mac_address: String,
+    service_name: Option<String>,
+    physical_adapter: Option<bool>,
+    #[serde(rename = "PNPDeviceID")]
+    pnp_device_id: Option<String>,
+    adapter_type_id: Option<u16>,
+}
+
+fn adapter_kind(adapter: &NetworkAdapterQueryResult) -> NetworkAdapterKind {
+    let pnp_device_id = adapter
+        .pnp_device_id
+        .as_deref()
+        .unwrap_or("")
+        .to_uppercase();
+    let is_virtual = !adapter.physical_adapter.unwrap_or(false)
+        || VIRTUAL_PNP_PREFIXES
+            .iter()
+            .any(|prefix| pnp_device_id.starts_with(prefix));
+
+    match is_virtual {
+        true => NetworkAdapterKind::Virtual,
+        false if adapter.adapter_type_id == Some(ADAPTER_TYPE_WIRELESS) => {
+            NetworkAdapterKind::Wireless
+        }
+        false => NetworkAdapterKind::Physical,
+    }
+}
+
+pub fn list_network_adapters() -> Vec<NetworkAdapter> {
+    let res: Vec<NetworkAdapterQueryResult> =
+        WmiSingleton::raw_query(NETWORK_ADAPTERS_QUERY).unwrap_or_default();
+
+    let mut adapters: Vec<NetworkAdapter> = res
+        .iter()
+        .map(|adapter| NetworkAdapter {
+            name: adapter.name.trim().to_string(),
+            mac_address: adapter.mac_address.trim().to_string(),
+            driver: adapter.service_name.to_owned(),
+            kind: adapter_kind(adapter),
+        })
+        .collect();
+
+    adapters.sort_by(|a, b| a.name.cmp(&b.name));
+    adapters
+}
@@ -4,17 +4,46 @@ use serde;
 #[cfg(target_os = "windows")]
 use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
 
-use crate::core::internal::{BaseDeviceInfoBuilder, IDeviceInfoBuilder};
+use crate::core::error::DeviceInfoError;
+use crate::core::internal::{BaseDeviceInfoBuilder, DuplicatePolicy, IDeviceInfoBuilder};
+#[cfg(target_os = "windows")]
+use crate::core::network::physical_mac_addresses;
+use crate::core::normalize::Normalizer;
+use crate::core::placeholder::PlaceholderPolicy;
+use crate::core::provenance::{Confidence, Provenance};
 #[cfg(target_os = "windows")]
 use crate::core::string_tools::strip_trailing_newline;
+use crate::core::value::ComponentValue;
+#[cfg(target_os = "windows")]
+use crate::plugins::windows::network::list_network_adapters;
 #[cfg(target_os = "windows")]
 use crate::plugins::windows::wmi::WmiSingleton;
 
+const USER_NAME_QUERY: &str =
+    "SELECT UserName FROM Win32_ComputerSystem WHERE UserName IS NOT NULL";
+const DISK_SERIAL_QUERY: &str =
+    "SELECT SerialNumber FROM Win32_PhysicalMedia WHERE SerialNumber IS NOT NULL";
+const BOARD_SERIAL_QUERY: &str =
+    "SELECT SerialNumber FROM Win32_BaseBoard WHERE SerialNumber IS NOT NULL";
+const SYSTEM_UUID_QUERY: &str =
+    "SELECT UUID FROM Win32_ComputerSystemProduct WHERE UUID IS NOT NULL";
+const PROCESSOR_ID_QUERY: &str =
+    "SELECT ProcessorId FROM Win32_Processor WHERE ProcessorId IS NOT NULL";
+pub const TOTAL_MEMORY_QUERY: &str = "SELECT TotalPhysicalMemory FROM Win32_ComputerSystem";
+pub const MEMORY_MODULES_QUERY: &str =
+    "SELECT DeviceLocator, Capacity, Manufacturer, PartNumber, SerialNumber \
+     FROM Win32_PhysicalMemory";
+pub const NETWORK_ADAPTERS_QUERY: &str =
+    "SELECT Name, MACAddress, ServiceName, PhysicalAdapter, PNPDeviceID, AdapterTypeId \
+     FROM Win32_NetworkAdapter WHERE MACAddress IS NOT NULL";
+const MACHINE_GUID_KEY: &str = "SOFTWARE\\Microsoft\\Cryptography";
+
 #[allow(dead_code)]
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
 pub enum WindowsBuilderComponents {
     LogonUserName,
     SystemDriveSerialNumber,
+    DiskSerialNumbers,
     MotherBoardSerialNumber,
     SystemUuid,
     MACAddress,
@@ -38,6 +67,7 @@ impl WindowsBuilderComponents {
             WindowsBuilderComponents::SystemDriveSerialNumber => {
                 "systemDriveSerialNumber".to_string()
             }
+            WindowsBuilderComponents::DiskSerialNumbers => "diskSerialNumbers".to_string(),
             WindowsBuilderComponents::MotherBoardSerialNumber => {
                 "motherBoardSerialNumber".to_string()
             }
@@ -48,10 +78,12 @@ impl WindowsBuilderComponents {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "logonUserName" => Some(WindowsBuilderComponents::LogonUserName),
             "systemDriveSerialNumber" => Some(WindowsBuilderComponents::SystemDriveSerialNumber),
+            "diskSerialNumbers" => Some(WindowsBuilderComponents::DiskSerialNumbers),
             "motherBoardSerialNumber" => Some(WindowsBuilderComponents::MotherBoardSerialNumber),
             "systemUuid" => Some(WindowsBuilderComponents::SystemUuid),
             "MACAddress" => Some(WindowsBuilderComponents::MACAddress),
@@ -60,6 +92,49 @@ impl WindowsBuilderComponents {
             _ => None,
         }
     }
+
+    pub fn normalizer(&self) -> Normalizer {
+        match *self {
+            WindowsBuilderComponents::SystemUuid => Normalizer::SmbiosUuid,
+            WindowsBuilderComponents::Guid => Normalizer::Uuid,
+            WindowsBuilderComponents::MACAddress => Normalizer::Mac,
+            WindowsBuilderComponents::SystemDriveSerialNumber
+            | WindowsBuilderComponents::DiskSerialNumbers
+            | WindowsBuilderComponents::MotherBoardSerialNumber
+            | WindowsBuilderComponents::ProcessorId => Normalizer::Serial,
+            WindowsBuilderComponents::LogonUserName => Normalizer::Trim,
+        }
+    }
+
+    /// The WMI query or registry value read
+    pub fn source(&self) -> String {
+        match *self {
+            WindowsBuilderComponents::LogonUserName => format!("WMI: {}", USER_NAME_QUERY),
+            WindowsBuilderComponents::SystemDriveSerialNumber
+            | WindowsBuilderComponents::DiskSerialNumbers => {
+                format!("WMI: {}", DISK_SERIAL_QUERY)
+            }
+            WindowsBuilderComponents::MotherBoardSerialNumber => {
+                format!("WMI: {}", BOARD_SERIAL_QUERY)
+            }
+            WindowsBuilderComponents::SystemUuid => format!("WMI: {}", SYSTEM_UUID_QUERY),
+            WindowsBuilderComponents::MACAddress => format!("WMI: {}", NETWORK_ADAPTERS_QUERY),
+            WindowsBuilderComponents::ProcessorId => format!("WMI: {}", PROCESSOR_ID_QUERY),
+            WindowsBuilderComponents::Guid => {
+                format!("Registry: HKLM\\{}\\MachineGuid", MACHINE_GUID_KEY)
+            }
+        }
+    }
+
+    /// The system drive is taken to be the first physical medium, and the
+    /// MAC address the first physical adapter
+    pub fn confidence(&self) -> Confidence {
+        match *self {
+            WindowsBuilderComponents::SystemDriveSerialNumber
+            | WindowsBuilderComponents::MACAddress => Confidence::Medium,
+            _ => Confidence::High,
+        }
+    }
 }
 impl fmt::Display for WindowsBuilderComponents {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -70,6 +145,7 @@ impl fmt::Display for WindowsBuilderComponents {
 pub trait IWindowsBuilder: IDeviceInfoBuilder<WindowsBuilderComponents> {
     fn add_logon_user_name(&mut self) -> &mut Self;
     fn add_system_drive_serial_number(&mut self) -> &mut Self;
+    fn add_disk_serial_numbers(&mut self) -> &mut Self;
     fn add_mother_board_serial_number(&mut self) -> &mut Self;
     fn add_system_uuid(&mut self) -> &mut Self;
     fn add_mac_address(&mut self) -> &mut Self;
@@ -89,15 +165,6 @@ impl WindowsBuilder {
     }
 }
 
-impl IDeviceInfoBuilder<WindowsBuilderComponents> for WindowsBuilder {
-    fn get_components(&self) -> &HashMap<WindowsBuilderComponents, String> {
-        &self._base.components
-    }
-    fn get_components_mut(&mut self) -> &mut HashMap<WindowsBuilderComponents, String> {
-        &mut self._base.components
-    }
-}
-
 #[cfg(target_os = "windows")]
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -122,178 +189,158 @@ struct UUIDQueryResult {
 #[cfg(target_os = "windows")]
 #[derive(serde::Deserialize)]
 #[allow(non_snake_case)]
-struct MACAddressQueryResult {
-    MACAddress: String,
+struct ProcessorIdQueryResult {
+    ProcessorId: String,
 }
 
 #[cfg(target_os = "windows")]
-#[derive(serde::Deserialize)]
-#[allow(non_snake_case)]
-struct ProcessorIdQueryResult {
-    ProcessorId: String,
+fn probe_windows(name: &WindowsBuilderComponents) -> Result<ComponentValue, DeviceInfoError> {
+    let clean = |value: &str| ComponentValue::from(strip_trailing_newline(value.trim()));
+
+    match *name {
+        WindowsBuilderComponents::LogonUserName => {
+            let res: UserNameQueryResult = WmiSingleton::first(USER_NAME_QUERY)?;
+            Ok(clean(&res.user_name))
+        }
+        WindowsBuilderComponents::SystemDriveSerialNumber => {
+            let res: SerialNumberQueryResult = WmiSingleton::first(DISK_SERIAL_QUERY)?;
+            Ok(clean(&res.serial_number))
+        }
+        WindowsBuilderComponents::DiskSerialNumbers => {
+            let res: Vec<SerialNumberQueryResult> = WmiSingleton::raw_query(DISK_SERIAL_QUERY)?;
+            Ok(ComponentValue::list(
+                res.iter()
+                    .map(|r| r.serial_number.trim())
+                    .filter(|serial| !serial.is_empty()),
+            ))
+        }
+        WindowsBuilderComponents::MotherBoardSerialNumber => {
+            let res: SerialNumberQueryResult = WmiSingleton::first(BOARD_SERIAL_QUERY)?;
+            Ok(clean(&res.serial_number))
+        }
+        WindowsBuilderComponents::SystemUuid => {
+            let res: UUIDQueryResult = WmiSingleton::first(SYSTEM_UUID_QUERY)?;
+            Ok(clean(&res.uuid))
+        }
+        WindowsBuilderComponents::MACAddress => physical_mac_addresses(&list_network_adapters())
+            .into_iter()
+            .next()
+            .map(ComponentValue::from)
+            .ok_or_else(|| DeviceInfoError::NotFound("Physical network adapter".to_string())),
+        WindowsBuilderComponents::ProcessorId => {
+            let res: ProcessorIdQueryResult = WmiSingleton::first(PROCESSOR_ID_QUERY)?;
+            Ok(clean(&res.ProcessorId))
+        }
+        WindowsBuilderComponents::Guid => {
+            let rkey = RegKey::predef(HKEY_LOCAL_MACHINE)
+                .open_subkey(MACHINE_GUID_KEY)
+                .map_err(|e| DeviceInfoError::from_io(MACHINE_GUID_KEY, e))?;
+            rkey.get_value::<String, _>("MachineGuid")
+                .map(ComponentValue::from)
+                .map_err(|e| DeviceInfoError::from_io("MachineGuid", e))
+        }
+    }
 }
 
-impl IWindowsBuilder for WindowsBuilder {
-    fn add_logon_user_name(&mut self) -> &mut Self {
+impl IDeviceInfoBuilder<WindowsBuilderComponents> for WindowsBuilder {
+    fn get_components(&self) -> &HashMap<WindowsBuilderComponents, ComponentValue> {
+        &self._base.components
+    }
+    fn get_components_mut(&mut self) -> &mut HashMap<WindowsBuilderComponents, ComponentValue> {
+        &mut self._base.components
+    }
+    fn get_failures(&self) -> &HashMap<WindowsBuilderComponents, DeviceInfoError> {
+        &self._base.failures
+    }
+    fn get_failures_mut(&mut self) -> &mut HashMap<WindowsBuilderComponents, DeviceInfoError> {
+        &mut self._base.failures
+    }
+    fn get_provenance(&self) -> &HashMap<WindowsBuilderComponents, Provenance> {
+        &self._base.provenance
+    }
+    fn get_provenance_mut(&mut self) -> &mut HashMap<WindowsBuilderComponents, Provenance> {
+        &mut self._base.provenance
+    }
+    fn get_duplicate_policy(&self) -> DuplicatePolicy {
+        self._base.duplicate_policy
+    }
+    fn set_duplicate_policy(&mut self, policy: DuplicatePolicy) -> &mut Self {
+        self._base.duplicate_policy = policy;
+        self
+    }
+
+    fn get_placeholder_policy(&self) -> PlaceholderPolicy {
+        self._base.placeholder_policy
+    }
+
+    fn set_placeholder_policy(&mut self, policy: PlaceholderPolicy) -> &mut Self {
+        self._base.placeholder_policy = policy;
+        self
+    }
+
+    fn normalize(&self, name: &WindowsBuilderComponents, value: ComponentValue) -> ComponentValue {
+        name.normalizer().apply(value)
+    }
+
+    fn is_identifier(&self, name: &WindowsBuilderComponents) -> bool {
+        name.normalizer().is_identifier()
+    }
+
+    fn source(&self, name: &WindowsBuilderComponents) -> String {
+        name.source()
+    }
+
+    fn confidence(&self, name: &WindowsBuilderComponents) -> Confidence {
+        name.confidence()
+    }
+
+    fn probe(&self, name: &WindowsBuilderComponents) -> Result<ComponentValue, DeviceInfoError> {
         #[cfg(target_os = "windows")]
         {
-            let res: Vec<UserNameQueryResult> = WmiSingleton::raw_query(
-                "SELECT UserName FROM Win32_ComputerSystem WHERE UserName IS NOT NULL",
-            );
-            self.add_component(
-                &WindowsBuilderComponents::LogonUserName,
-                strip_trailing_newline(
-                    res.get(0)
-                        .and_then(|r| Some(r.user_name.as_str()))
-                        .unwrap_or("")
-                        .trim(),
-                ),
-            );
-            self
+            probe_windows(name)
         }
 
         #[cfg(not(target_os = "windows"))]
         {
-            todo!()
+            Err(DeviceInfoError::Unsupported(format!(
+                "Windows::{} is only available on Windows",
+                name
+            )))
         }
     }
+}
+
+impl IWindowsBuilder for WindowsBuilder {
+    fn add_logon_user_name(&mut self) -> &mut Self {
+        self.add_probed(&WindowsBuilderComponents::LogonUserName)
+    }
+
     fn add_system_drive_serial_number(&mut self) -> &mut Self {
-        #[cfg(target_os = "windows")]
-        {
-            let res: Vec<SerialNumberQueryResult> = WmiSingleton::raw_query(
-                "SELECT SerialNumber FROM Win32_PhysicalMedia WHERE SerialNumber IS NOT NULL",
-            );
-
-            self.add_component(
-                &WindowsBuilderComponents::SystemDriveSerialNumber,
-                strip_trailing_newline(
-                    res.get(0)
-                        .and_then(|r| Some(r.serial_number.as_str()))
-                        .unwrap_or("")
-                        .trim(),
-                ),
-            );
-            self
-        }
+        self.add_probed(&WindowsBuilderComponents::SystemDriveSerialNumber)
+    }
 
-        #[cfg(not(target_os = "windows"))]
-        {
-            todo!()
-        }
+    fn add_disk_serial_numbers(&mut self) -> &mut Self {
+        self.add_probed(&WindowsBuilderComponents::DiskSerialNumbers)
     }
 
     fn add_mother_board_serial_number(&mut self) -> &mut Self {
-        #[cfg(target_os = "windows")]
-        {
-            let res: Vec<SerialNumberQueryResult> = WmiSingleton::raw_query(
-                "SELECT SerialNumber FROM Win32_BaseBoard WHERE SerialNumber IS NOT NULL",
-            );
-
-            self.add_component(
-                &WindowsBuilderComponents::MotherBoardSerialNumber,
-                strip_trailing_newline(
-                    res.get(0)
-                        .and_then(|r| Some(r.serial_number.as_str()))
-                        .unwrap_or("")
-                        .trim(),
-                ),
-            );
-            self
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            todo!()
-        }
+        self.add_probed(&WindowsBuilderComponents::MotherBoardSerialNumber)
     }
 
     fn add_system_uuid(&mut self) -> &mut Self {
-        #[cfg(target_os = "windows")]
-        {
-            let res: Vec<UUIDQueryResult> = WmiSingleton::raw_query(
-                "SELECT UUID FROM Win32_ComputerSystemProduct WHERE UUID IS NOT NULL",
-            );
-
-            self.add_component(
-                &WindowsBuilderComponents::SystemUuid,
-                strip_trailing_newline(
-                    res.get(0)
-                        .and_then(|r| Some(r.uuid.as_str()))
-                        .unwrap_or("")
-                        .trim(),
-                ),
-            );
-            self
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            todo!()
-        }
+        self.add_probed(&WindowsBuilderComponents::SystemUuid)
     }
 
     fn add_mac_address(&mut self) -> &mut Self {
-        #[cfg(target_os = "windows")]
-        {
-            let res: Vec<MACAddressQueryResult> = WmiSingleton::raw_query(
-                "SELECT MACAddress FROM Win32_NetworkAdapter WHERE MACAddress IS NOT NULL",
-            );
-
-            self.add_component(
-                &WindowsBuilderComponents::MACAddress,
-                strip_trailing_newline(strip_trailing_newline(
-                    res.get(0)
-                        .and_then(|r| Some(r.MACAddress.as_str()))
-                        .unwrap_or("")
-                        .trim(),
-                )),
-            );
-            self
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            todo!()
-        }
+        self.add_probed(&WindowsBuilderComponents::MACAddress)
     }
 
     fn add_processor_id(&mut self) -> &mut Self {
-        #[cfg(target_os = "windows")]
-        {
-            let res: Vec<ProcessorIdQueryResult> = WmiSingleton::raw_query(
-                "SELECT ProcessorId FROM Win32_Processor WHERE ProcessorId IS NOT NULL",
-            );
-
-            self.add_component(
-                &WindowsBuilderComponents::ProcessorId,
-                strip_trailing_newline(strip_trailing_newline(
-                    res.get(0)
-                        .and_then(|r| Some(r.ProcessorId.as_str()))
-                        .unwrap_or("")
-                        .trim(),
-                )),
-            );
-            self
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            todo!()
-        }
+        self.add_probed(&WindowsBuilderComponents::ProcessorId)
     }
 
     fn add_machine_guid(&mut self) -> &mut Self {
-        #[cfg(target_os = "windows")]
-        {
-            let rkey = RegKey::predef(HKEY_LOCAL_MACHINE)
-                .open_subkey("SOFTWARE\\Microsoft\\Cryptography")
-                .unwrap();
-
-            let id: String = rkey.get_value("MachineGuid").unwrap();
-
-            self.add_component(&WindowsBuilderComponents::Guid, id.as_str());
-            self
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            todo!()
-        }
+        self.add_probed(&WindowsBuilderComponents::Guid)
     }
 }
 
@@ -2,19 +2,38 @@ use serde::de::DeserializeOwned;
 
 use wmi::{COMLibrary, WMIConnection};
 
+use crate::core::error::DeviceInfoError;
+
 thread_local! {
-    static COM_LIB: COMLibrary = COMLibrary::without_security().unwrap();
+    static COM_LIB: Option<COMLibrary> = COMLibrary::without_security().ok();
 }
 
 pub struct WmiSingleton;
 
 impl WmiSingleton {
-    pub fn raw_query<T>(query: &str) -> Vec<T>
+    pub fn raw_query<T>(query: &str) -> Result<Vec<T>, DeviceInfoError>
+    where
+        T: DeserializeOwned,
+    {
+        let com_lib = COM_LIB
+            .with(|com_lib| *com_lib)
+            .ok_or_else(|| DeviceInfoError::ProbeFailed("Failed to initialize COM".to_string()))?;
+        let con = WMIConnection::new(com_lib).map_err(|e| {
+            DeviceInfoError::ProbeFailed(format!("Failed to connect to WMI: {}", e))
+        })?;
+        con.raw_query::<T>(query).map_err(|e| {
+            DeviceInfoError::ProbeFailed(format!("Failed to execute query {}: {}", query, e))
+        })
+    }
+
+    /// First result of `query`, `NotFound` when it returns no instance
+    pub fn first<T>(query: &str) -> Result<T, DeviceInfoError>
     where
         T: DeserializeOwned,
     {
-        let con = WMIConnection::new(COM_LIB.with(|con| *con)).expect("Failed to connect to WMI");
-        con.raw_query::<T>(query)
-            .expect(format!("Failed to execute query {}", query).as_str())
+        Self::raw_query::<T>(query)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| DeviceInfoError::NotFound(format!("No result for query {}", query)))
     }
 }